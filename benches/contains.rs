@@ -0,0 +1,34 @@
+//! Compares `contains_left` with `get_left(..).is_some()`, over a map too large to stay in cache.
+//! `contains_left` never reads the right side of the map, so it should save a cache miss per hit.
+
+use isomorphism::BiMap;
+
+use std::time::Instant;
+
+const PAIRS: u64 = 1_000_000;
+const RUNS: u32 = 10;
+
+fn main() {
+    let map: BiMap<u64, u64> = (0..PAIRS).map(|i| (i, PAIRS + i)).collect();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        for i in 0..PAIRS {
+            assert!(map.contains_left(&i));
+        }
+    }
+    let contains = start.elapsed() / RUNS;
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        for i in 0..PAIRS {
+            assert!(map.get_left(&i).is_some());
+        }
+    }
+    let get = start.elapsed() / RUNS;
+
+    println!(
+        "checking {} left keys: contains_left {:?}, get_left {:?} per run",
+        PAIRS, contains, get
+    );
+}
@@ -0,0 +1,31 @@
+//! Measures how long it takes to deserialize a large `BiMap` from a format that doesn't report how
+//! many pairs there are up front.
+
+use isomorphism::BiMap;
+
+use std::time::Instant;
+
+const PAIRS: u64 = 100_000;
+const RUNS: u32 = 10;
+
+fn main() {
+    let json = format!(
+        "{{{}}}",
+        (0..PAIRS)
+            .map(|i| format!("\"{}\": {}", i, PAIRS + i))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let map: BiMap<String, u64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(PAIRS as usize, map.len());
+    }
+    let elapsed = start.elapsed() / RUNS;
+
+    println!(
+        "deserializing {} pairs without a length: {:?} per run",
+        PAIRS, elapsed
+    );
+}
@@ -0,0 +1,21 @@
+//! Measures how long it takes to compare two equal `BiMap`s that were filled in different orders.
+
+use isomorphism::BiMap;
+
+use std::time::Instant;
+
+const PAIRS: u64 = 100_000;
+const RUNS: u32 = 20;
+
+fn main() {
+    let forwards: BiMap<u64, u64> = (0..PAIRS).map(|i| (i, PAIRS + i)).collect();
+    let backwards: BiMap<u64, u64> = (0..PAIRS).rev().map(|i| (i, PAIRS + i)).collect();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        assert!(forwards == backwards);
+    }
+    let elapsed = start.elapsed() / RUNS;
+
+    println!("comparing maps of {} pairs: {:?} per run", PAIRS, elapsed);
+}
@@ -0,0 +1,38 @@
+//! Measures how long it takes to insert `String` pairs into a `BiMap`, where hashing the keys is a
+//! significant part of the cost. Every key is inserted twice, so that the second pass replaces the
+//! existing pairs.
+
+use isomorphism::BiMap;
+
+use std::time::Instant;
+
+const PAIRS: usize = 50_000;
+const RUNS: u32 = 10;
+
+fn main() {
+    let pairs: Vec<(String, String)> = (0..PAIRS)
+        .map(|i| {
+            (
+                format!("a reasonably long left key, number {}", i),
+                format!("a reasonably long right key, number {}", i),
+            )
+        })
+        .collect();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let mut map = BiMap::with_capacity(PAIRS);
+        for _ in 0..2 {
+            for (left, right) in pairs.iter().cloned() {
+                map.insert(left, right);
+            }
+        }
+        assert_eq!(PAIRS, map.len());
+    }
+    let elapsed = start.elapsed() / RUNS;
+
+    println!(
+        "inserting {} string pairs twice: {:?} per run",
+        PAIRS, elapsed
+    );
+}
@@ -0,0 +1,26 @@
+//! Measures how long it takes to look up every key in a populated `BiMap`, from both sides.
+
+use isomorphism::BiMap;
+
+use std::time::Instant;
+
+const PAIRS: u64 = 100_000;
+const RUNS: u32 = 20;
+
+fn main() {
+    let map: BiMap<u64, u64> = (0..PAIRS).map(|i| (i, PAIRS + i)).collect();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        for i in 0..PAIRS {
+            assert_eq!(Some(&(PAIRS + i)), map.get_left(&i));
+            assert_eq!(Some(&i), map.get_right(&(PAIRS + i)));
+        }
+    }
+    let elapsed = start.elapsed() / RUNS;
+
+    println!(
+        "looking up {} pairs from both sides: {:?} per run",
+        PAIRS, elapsed
+    );
+}
@@ -0,0 +1,24 @@
+//! Measures how long it takes to grow a `BiMap` of `String` keys from empty, which is dominated
+//! by the cost of the resizes along the way.
+
+use isomorphism::BiMap;
+
+use std::time::Instant;
+
+const PAIRS: usize = 100_000;
+const RUNS: u32 = 10;
+
+fn main() {
+    let pairs: Vec<_> = (0..PAIRS)
+        .map(|i| (format!("left key {}", i), format!("right key {}", i)))
+        .collect();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let map: BiMap<String, String> = pairs.iter().cloned().collect();
+        assert_eq!(PAIRS, map.len());
+    }
+    let elapsed = start.elapsed() / RUNS;
+
+    println!("inserting {} string pairs: {:?} per run", PAIRS, elapsed);
+}
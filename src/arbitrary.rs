@@ -0,0 +1,23 @@
+use crate::BiMap;
+
+use quickcheck::{Arbitrary, Gen};
+use std::hash::Hash;
+
+/// Generates a `BiMap` by inserting an arbitrary list of pairs in order, so any pairs that share a
+/// left or right key with a later pair are evicted. Shrinking removes pairs from the map, and
+/// shrinks the keys of the pairs that remain.
+impl<L, R> Arbitrary for BiMap<L, R>
+where
+    L: Arbitrary + Hash + Eq,
+    R: Arbitrary + Hash + Eq,
+{
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let pairs: Vec<(L, R)> = Arbitrary::arbitrary(g);
+        pairs.into_iter().collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let pairs: Vec<(L, R)> = self.clone().into_iter().collect();
+        Box::new(pairs.shrink().map(|pairs| pairs.into_iter().collect()))
+    }
+}
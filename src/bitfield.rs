@@ -1,6 +1,8 @@
 //! Definitions of bitfield things for hashmap neighbourhoods.
-use std::iter::Iterator;
-use std::ops::{BitAnd, BitOr, Shr};
+use self::private::BitSized;
+
+use core::iter::{DoubleEndedIterator, Iterator};
+use core::ops::{BitAnd, BitOr, Not, Shl, Shr};
 
 /// A bit field trait for use in hashmap buckets. See the `bitfield` method of `BiMapBuilder` for
 /// more information.
@@ -17,47 +19,156 @@ pub trait BitField: BitAnd<Output = Self> + BitOr<Output = Self> + Copy + Sized
     /// Should return a bitfield that is all ones, except for a single zero at a given index.
     fn zero_at(index: usize) -> Self;
 
+    /// Returns a bitfield that is all zeroes.
+    fn empty() -> Self {
+        Self::one_at(0) & Self::zero_at(0)
+    }
+
+    /// Is the bitfield currently all zeroes?
+    fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
     /// Return an iterator that iterates through the bitfield, returning the indexes within the
     /// bitfield that have 1s in them, in order from least significant to most significant.
     fn iter(&self) -> Self::Iter;
 
     /// Is the bitfield currently full?
     fn full(&self) -> bool;
+
+    /// Returns the number of ones in the bitfield.
+    fn count_ones(&self) -> usize {
+        self.iter().count()
+    }
 }
 
 mod private {
     use super::{BitField, BitFieldIterator};
 
-    use std::ops::{BitAnd, BitOr, Not, Shl, Shr};
+    use core::mem;
+    use core::ops::{BitAnd, BitOr, Not, Shl, Shr};
 
     /// Helper trait to reduce code duplication when implementing Bitfield for integer types.
     pub trait BitSized {
         /// Returns how many bits are in the type.
         fn size() -> usize;
+
+        /// Returns how many ones are in the value.
+        fn count_ones(&self) -> usize;
+
+        /// Returns how many zeros are below the least significant one in the value.
+        fn trailing_zeros(&self) -> usize;
+
+        /// Returns how many zeros are above the most significant one in the value.
+        fn leading_zeros(&self) -> usize;
     }
 
     impl BitSized for u8 {
         fn size() -> usize {
             8
         }
+
+        fn count_ones(&self) -> usize {
+            u8::count_ones(*self) as usize
+        }
+
+        fn trailing_zeros(&self) -> usize {
+            u8::trailing_zeros(*self) as usize
+        }
+
+        fn leading_zeros(&self) -> usize {
+            u8::leading_zeros(*self) as usize
+        }
     }
 
     impl BitSized for u16 {
         fn size() -> usize {
             16
         }
+
+        fn count_ones(&self) -> usize {
+            u16::count_ones(*self) as usize
+        }
+
+        fn trailing_zeros(&self) -> usize {
+            u16::trailing_zeros(*self) as usize
+        }
+
+        fn leading_zeros(&self) -> usize {
+            u16::leading_zeros(*self) as usize
+        }
     }
 
     impl BitSized for u32 {
         fn size() -> usize {
             32
         }
+
+        fn count_ones(&self) -> usize {
+            u32::count_ones(*self) as usize
+        }
+
+        fn trailing_zeros(&self) -> usize {
+            u32::trailing_zeros(*self) as usize
+        }
+
+        fn leading_zeros(&self) -> usize {
+            u32::leading_zeros(*self) as usize
+        }
     }
 
     impl BitSized for u64 {
         fn size() -> usize {
             64
         }
+
+        fn count_ones(&self) -> usize {
+            u64::count_ones(*self) as usize
+        }
+
+        fn trailing_zeros(&self) -> usize {
+            u64::trailing_zeros(*self) as usize
+        }
+
+        fn leading_zeros(&self) -> usize {
+            u64::leading_zeros(*self) as usize
+        }
+    }
+
+    impl BitSized for u128 {
+        fn size() -> usize {
+            128
+        }
+
+        fn count_ones(&self) -> usize {
+            u128::count_ones(*self) as usize
+        }
+
+        fn trailing_zeros(&self) -> usize {
+            u128::trailing_zeros(*self) as usize
+        }
+
+        fn leading_zeros(&self) -> usize {
+            u128::leading_zeros(*self) as usize
+        }
+    }
+
+    impl BitSized for usize {
+        fn size() -> usize {
+            mem::size_of::<usize>() * 8
+        }
+
+        fn count_ones(&self) -> usize {
+            usize::count_ones(*self) as usize
+        }
+
+        fn trailing_zeros(&self) -> usize {
+            usize::trailing_zeros(*self) as usize
+        }
+
+        fn leading_zeros(&self) -> usize {
+            usize::leading_zeros(*self) as usize
+        }
     }
 
     impl<T> BitField for T
@@ -86,12 +197,26 @@ mod private {
             !Self::one_at(index)
         }
 
+        fn empty() -> Self {
+            Self::from(0)
+        }
+
+        fn is_empty(&self) -> bool {
+            *self == Self::from(0)
+        }
+
         fn iter(&self) -> Self::Iter {
             BitFieldIterator(*self, 0)
         }
 
         fn full(&self) -> bool {
-            *self == Self::one_at(0) | Self::zero_at(0)
+            // compare the complement against zero rather than building an all-ones value, so that
+            // no shifts are involved whatever the width of the type
+            !*self == Self::from(0)
+        }
+
+        fn count_ones(&self) -> usize {
+            <T as BitSized>::count_ones(self)
         }
     }
 }
@@ -101,7 +226,7 @@ pub struct BitFieldIterator<T>(T, usize);
 
 impl<T> Iterator for BitFieldIterator<T>
 where
-    T: Eq + BitAnd<Output = T> + Shr<usize, Output = T> + From<u8> + Copy,
+    T: BitSized + Eq + Shr<usize, Output = T> + From<u8> + Copy,
 {
     type Item = usize;
 
@@ -111,23 +236,180 @@ where
         if *bitfield == T::from(0) {
             None
         } else {
-            while T::from(1) & *bitfield == T::from(0) {
-                *bitfield = *bitfield >> 1;
-                *index += 1;
-            }
-            *bitfield = *bitfield >> 1;
-            *index += 1;
+            // jump straight to the next one, shifting past it separately so that the shift can
+            // never be as wide as the type
+            let zeros = bitfield.trailing_zeros();
+            *bitfield = (*bitfield >> zeros) >> 1;
+            *index += zeros + 1;
             Some(*index - 1)
         }
     }
 }
 
+impl<T> DoubleEndedIterator for BitFieldIterator<T>
+where
+    T: BitSized
+        + BitAnd<Output = T>
+        + Eq
+        + Not<Output = T>
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + From<u8>
+        + Copy,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let &mut BitFieldIterator(ref mut bitfield, index) = self;
+
+        if *bitfield == T::from(0) {
+            None
+        } else {
+            // the highest one is cleared in place, so the next call from the front is unaffected
+            let highest = T::size() - 1 - bitfield.leading_zeros();
+            *bitfield = *bitfield & !(T::from(1) << highest);
+            Some(index + highest)
+        }
+    }
+}
+
 /// The default bitfield type.
 pub type DefaultBitField = u32;
 
+/// A bitfield made up of `N` 64 bit limbs, for neighbourhoods wider than the primitive integer
+/// types allow. Bit `i` lives in limb `i / 64`, at position `i % 64` within that limb. `N` must be
+/// at least one, as a neighbourhood with no room for any keys can't hold anything - using
+/// `WideBitField<0>` as a bitfield fails to compile.
+///
+/// ```
+/// # use isomorphism::{BiMap, BiMapBuilder};
+/// use isomorphism::bitfield::WideBitField;
+///
+/// let mut map: BiMap<u32, char, _, _, WideBitField<4>> = BiMapBuilder::new()
+///             .bitfield::<WideBitField<4>>()
+///             .finish();
+/// map.insert(1, 'a');
+/// assert_eq!(Some(&'a'), map.get_left(&1));
+/// ```
+///
+/// ```compile_fail
+/// # use isomorphism::{BiMap, BiMapBuilder};
+/// use isomorphism::bitfield::WideBitField;
+///
+/// let mut map: BiMap<u32, char, _, _, WideBitField<0>> = BiMapBuilder::new()
+///             .bitfield::<WideBitField<0>>()
+///             .finish();
+/// map.insert(1, 'a');
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WideBitField<const N: usize>(pub [u64; N]);
+
+impl<const N: usize> WideBitField<N> {
+    /// Evaluating this fails to compile if the bitfield has no limbs.
+    const NOT_EMPTY: () = assert!(N > 0, "a WideBitField needs at least one limb");
+}
+
+impl<const N: usize> BitAnd for WideBitField<N> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        let WideBitField(mut limbs) = self;
+        for (limb, other) in limbs.iter_mut().zip(other.0.iter()) {
+            *limb &= *other;
+        }
+        WideBitField(limbs)
+    }
+}
+
+impl<const N: usize> BitOr for WideBitField<N> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        let WideBitField(mut limbs) = self;
+        for (limb, other) in limbs.iter_mut().zip(other.0.iter()) {
+            *limb |= *other;
+        }
+        WideBitField(limbs)
+    }
+}
+
+impl<const N: usize> BitField for WideBitField<N> {
+    type Iter = WideBitFieldIterator<N>;
+
+    fn size() -> usize {
+        let () = Self::NOT_EMPTY;
+        N * 64
+    }
+
+    fn one_at(index: usize) -> Self {
+        let () = Self::NOT_EMPTY;
+        let mut limbs = [0; N];
+        limbs[index / 64] = 1 << (index % 64);
+        WideBitField(limbs)
+    }
+
+    fn zero_at(index: usize) -> Self {
+        let () = Self::NOT_EMPTY;
+        let mut limbs = [u64::MAX; N];
+        limbs[index / 64] = !(1 << (index % 64));
+        WideBitField(limbs)
+    }
+
+    fn empty() -> Self {
+        let () = Self::NOT_EMPTY;
+        WideBitField([0; N])
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
+
+    fn iter(&self) -> Self::Iter {
+        WideBitFieldIterator {
+            limbs: self.0,
+            limb: 0,
+            current: BitFieldIterator(self.0.first().copied().unwrap_or(0), 0),
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.0.iter().all(|&limb| limb == u64::MAX)
+    }
+
+    fn count_ones(&self) -> usize {
+        self.0
+            .iter()
+            .map(|&limb| u64::count_ones(limb) as usize)
+            .sum()
+    }
+}
+
+/// An iterator over the active bits in a `WideBitField`, walking the limbs in order.
+pub struct WideBitFieldIterator<const N: usize> {
+    limbs: [u64; N],
+    limb: usize,
+    current: BitFieldIterator<u64>,
+}
+
+impl<const N: usize> Iterator for WideBitFieldIterator<N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(bit) = self.current.next() {
+                return Some(self.limb * 64 + bit);
+            }
+
+            self.limb += 1;
+            if self.limb >= N {
+                return None;
+            }
+            self.current = BitFieldIterator(self.limbs[self.limb], 0);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::BitField;
+    use super::{BitField, WideBitField};
 
     use quickcheck::TestResult;
 
@@ -154,7 +436,168 @@ mod test {
         fn iterator_results_equal_number(input: u32) -> bool {
             input == input.iter()
                 .map(|x| 1 << x)
-                .fold(0, |x, y| x + y)
+                .sum::<u32>()
+        }
+    }
+
+    #[test]
+    fn count_ones() {
+        assert_eq!(13, BitField::count_ones(&0xBEEFu16));
+        assert_eq!(0, BitField::count_ones(&0u32));
+        assert_eq!(8, BitField::count_ones(&u8::MAX));
+        assert_eq!(64, BitField::count_ones(&u64::MAX));
+    }
+
+    quickcheck! {
+        fn count_ones_matches_iter(input: u64) -> bool {
+            BitField::count_ones(&input) == input.iter().count()
+        }
+    }
+
+    #[test]
+    fn iterator_sparse_bits() {
+        let bits: Vec<_> = 0xBEEFu16.iter().collect();
+        assert_eq!(vec![0, 1, 2, 3, 5, 6, 7, 9, 10, 11, 12, 13, 15], bits);
+
+        let bits: Vec<_> = (1u64 << 63 | 1).iter().collect();
+        assert_eq!(vec![0, 63], bits);
+    }
+
+    #[test]
+    fn iterator_rev() {
+        let bits: Vec<_> = 0xBEEFu16.iter().rev().collect();
+        assert_eq!(vec![15, 13, 12, 11, 10, 9, 7, 6, 5, 3, 2, 1, 0], bits);
+
+        let bits: Vec<_> = (1u64 << 63 | 1).iter().rev().collect();
+        assert_eq!(vec![63, 0], bits);
+        assert_eq!(None, 0u8.iter().next_back());
+    }
+
+    quickcheck! {
+        fn iterator_both_ends(input: u32, from_back: Vec<bool>) -> bool {
+            // take from whichever end each flag says, then drain the rest from the front
+            let mut iter = input.iter();
+            let mut bits = Vec::new();
+            for &back in &from_back {
+                let bit = if back { iter.next_back() } else { iter.next() };
+                bits.extend(bit);
+            }
+            bits.extend(iter);
+
+            bits.sort_unstable();
+            bits == input.iter().collect::<Vec<_>>()
+        }
+    }
+
+    #[test]
+    fn wide_integers() {
+        assert_eq!(128, u128::size());
+        assert_eq!(std::mem::size_of::<usize>() * 8, usize::size());
+
+        assert!(u128::MAX.full());
+        assert!(!u128::zero_at(127).full());
+        assert!(!u128::zero_at(0).full());
+        assert!(usize::MAX.full());
+
+        let bits: Vec<_> = (u128::one_at(127) | u128::one_at(64) | u128::one_at(0))
+            .iter()
+            .collect();
+        assert_eq!(vec![0, 64, 127], bits);
+        assert_eq!(128, u128::MAX.iter().count());
+    }
+
+    #[test]
+    fn wide_limb_boundaries() {
+        type Wide = WideBitField<4>;
+        assert_eq!(256, Wide::size());
+
+        let low = Wide::one_at(63);
+        let high = Wide::one_at(64);
+        assert_eq!(WideBitField([1 << 63, 0, 0, 0]), low);
+        assert_eq!(WideBitField([0, 1, 0, 0]), high);
+        assert_eq!(vec![63, 64], (low | high).iter().collect::<Vec<_>>());
+        assert_eq!(
+            vec![64],
+            ((low | high) & Wide::zero_at(63))
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![255], Wide::one_at(255).iter().collect::<Vec<_>>());
+
+        let empty = Wide::empty();
+        assert_eq!(0, empty.iter().count());
+        assert_eq!(0, empty.count_ones());
+    }
+
+    #[test]
+    fn wide_full() {
+        type Wide = WideBitField<4>;
+        let full = Wide::one_at(0) | Wide::zero_at(0);
+        assert!(full.full());
+        assert_eq!(256, full.count_ones());
+        assert_eq!(
+            (0..256).collect::<Vec<_>>(),
+            full.iter().collect::<Vec<_>>()
+        );
+
+        for &index in &[0, 63, 64, 255] {
+            assert!(!(full & Wide::zero_at(index)).full());
+        }
+    }
+
+    fn check_full<B: BitField>(all_ones: B) {
+        assert!(all_ones.full());
+        for index in 0..B::size() {
+            assert!(!(all_ones & B::zero_at(index)).full());
+        }
+        assert!(!B::empty().full());
+    }
+
+    #[test]
+    fn full_for_each_width() {
+        check_full(u8::MAX);
+        check_full(u16::MAX);
+        check_full(u32::MAX);
+        check_full(u64::MAX);
+        check_full(u128::MAX);
+        check_full(usize::MAX);
+        check_full(WideBitField([u64::MAX; 2]));
+    }
+
+    fn check_empty<B: BitField>() {
+        let empty = B::empty();
+        assert!(empty.is_empty());
+        assert_eq!(0, empty.iter().count());
+        assert_eq!(0, empty.count_ones());
+        assert!(!empty.full());
+        for index in 0..B::size() {
+            assert!(!B::one_at(index).is_empty());
+            assert!((B::one_at(index) & B::zero_at(index)).is_empty());
+        }
+    }
+
+    #[test]
+    fn empty_for_each_width() {
+        check_empty::<u8>();
+        check_empty::<u16>();
+        check_empty::<u32>();
+        check_empty::<u64>();
+        check_empty::<u128>();
+        check_empty::<usize>();
+        check_empty::<WideBitField<3>>();
+    }
+
+    quickcheck! {
+        fn is_empty_matches_iter(input: u64) -> bool {
+            input.is_empty() == (input.iter().next().is_none())
+                && WideBitField([input, 0]).is_empty() == input.is_empty()
+        }
+    }
+
+    quickcheck! {
+        fn wide_one_at(index: usize) -> bool {
+            let index = index % 192;
+            WideBitField::<3>::one_at(index).iter().collect::<Vec<_>>() == vec![index]
         }
     }
 }
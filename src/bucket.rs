@@ -1,26 +1,71 @@
-use crate::bitfield::BitField;
+use crate::{
+    bitfield::BitField,
+    pair_index::{self, PairIndex},
+    TryReserveError,
+};
 
-/// A single bucket within a hopscotch hashed hashmap.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::mem;
+
+/// A single bucket within a hopscotch hashed hashmap. Buckets can be inspected through the
+/// `left_buckets` and `right_buckets` methods of `BiMap`, but only read - where a key is stored,
+/// and which bucket it is paired with, is an implementation detail that changes whenever the map
+/// is modified or resized.
 #[derive(Clone, Debug)]
-pub struct Bucket<K, V, B> {
-    /// Key, value, ideal hash position triplet.
-    pub data: Option<(K, V, usize)>,
-    /// A bitfield representing the next <sizeof bitfield> buckets in the hashmap (including this
+pub struct Bucket<K, I, B> {
+    /// Key, paired position, ideal hash position, hash quadruple. The hash is only valid for the
+    /// hasher that produced it, so it may be reused when the key moves within the same map, but
+    /// must be recomputed if the map's hasher is replaced.
+    pub(crate) data: Option<(K, I, I, u64)>,
+    /// A bitfield representing the next `B::size()` buckets in the hashmap (including this
     /// one). A one in this bitfield means that the bucket contains a value which should be in this
     /// bucket, a zero in this bitfield means that the bucket is either empty, or contains a value
     /// which should not be in this bucket.
-    pub neighbourhood: B,
+    pub(crate) neighbourhood: B,
 }
 
-impl<K, V, B: BitField + Copy> Bucket<K, V, B> {
-    /// Create a new heap allocated array, with a given size, of empty buckets.
-    pub fn empty_vec(size: usize) -> Box<[Self]> {
+impl<K, I: PairIndex, B: Copy> Bucket<K, I, B> {
+    /// Returns the key stored in this bucket, or `None` if the bucket is empty.
+    pub fn key(&self) -> Option<&K> {
+        self.data.as_ref().map(|(key, ..)| key)
+    }
+
+    /// Returns the position of the bucket on the other side of the map that holds the key this
+    /// one is paired with, or `None` if the bucket is empty.
+    pub fn pair_index(&self) -> Option<usize> {
+        self.data.as_ref().map(|&(_, pair, ..)| pair.to_usize())
+    }
+
+    /// Returns the position this bucket's key would be stored at if there were no collisions, or
+    /// `None` if the bucket is empty. The key is always within a neighbourhood of this position.
+    pub fn ideal_index(&self) -> Option<usize> {
+        self.data.as_ref().map(|&(_, _, ideal, _)| ideal.to_usize())
+    }
+
+    /// Returns the hash of this bucket's key, or `None` if the bucket is empty.
+    pub fn hash(&self) -> Option<u64> {
+        self.data.as_ref().map(|&(.., hash)| hash)
+    }
+
+    /// Returns the bitfield marking which of the buckets starting at this one hold keys whose
+    /// ideal position is this bucket.
+    pub fn neighbourhood(&self) -> B {
+        self.neighbourhood
+    }
+}
+
+impl<K, I: PairIndex, B: BitField + Copy> Bucket<K, I, B> {
+    /// Create a new heap allocated array, with a given size, of empty buckets. Panics if the
+    /// positions of that many buckets don't fit in the pair index type.
+    pub(crate) fn empty_vec(size: usize) -> Box<[Self]> {
+        assert!(pair_index::fits::<I>(size), "capacity overflow");
         let mut output = Vec::with_capacity(size);
 
         for _ in 0..size {
             let element: Self = Bucket {
                 data: None,
-                neighbourhood: B::one_at(0) & B::zero_at(0),
+                neighbourhood: B::empty(),
             };
 
             output.push(element);
@@ -28,25 +73,62 @@ impl<K, V, B: BitField + Copy> Bucket<K, V, B> {
 
         output.into()
     }
+
+    /// Create a new heap allocated array, with a given size, of empty buckets. Returns an error
+    /// rather than aborting if the memory can't be allocated, or panicking if the positions of
+    /// that many buckets don't fit in the pair index type.
+    pub(crate) fn try_empty_vec(size: usize) -> Result<Box<[Self]>, TryReserveError> {
+        match size.checked_mul(mem::size_of::<Self>()) {
+            Some(bytes) if bytes <= isize::MAX as usize && pair_index::fits::<I>(size) => (),
+            _ => return Err(TryReserveError::CapacityOverflow),
+        }
+
+        let mut output = Vec::new();
+        output
+            .try_reserve_exact(size)
+            .map_err(|_| TryReserveError::AllocError)?;
+
+        for _ in 0..size {
+            output.push(Bucket {
+                data: None,
+                neighbourhood: B::empty(),
+            });
+        }
+
+        Ok(output.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{bitfield::DefaultBitField, bucket::Bucket};
+    use crate::{bitfield::DefaultBitField, bucket::Bucket, TryReserveError};
 
     #[test]
     fn test_empty_vec() {
-        let vec: Box<[Bucket<(), (), DefaultBitField>]> = Bucket::empty_vec(0);
-        assert!(vec.len() == 0)
+        let vec: Box<[Bucket<(), usize, DefaultBitField>]> = Bucket::empty_vec(0);
+        assert!(vec.is_empty())
+    }
+
+    #[test]
+    fn test_try_empty_vec() {
+        let vec: Box<[Bucket<(), usize, DefaultBitField>]> = Bucket::try_empty_vec(64).unwrap();
+        assert_eq!(64, vec.len());
+        assert!(vec.iter().all(|element| element.data.is_none()));
+
+        let overflow = Bucket::<(), usize, DefaultBitField>::try_empty_vec(usize::MAX);
+        assert_eq!(Some(TryReserveError::CapacityOverflow), overflow.err());
+
+        let narrow = Bucket::<(), u16, DefaultBitField>::try_empty_vec((1 << 16) + 1);
+        assert_eq!(Some(TryReserveError::CapacityOverflow), narrow.err());
     }
 
     #[test]
     fn test_full_vec() {
         let length = 1024;
-        let vec: Box<[Bucket<(), (), DefaultBitField>]> = Bucket::empty_vec(length);
+        let vec: Box<[Bucket<(), usize, DefaultBitField>]> = Bucket::empty_vec(length);
         assert!(vec.len() == length);
         assert!(vec
             .iter()
-            .all(|element| element.data == None && element.neighbourhood == 0));
+            .all(|element| element.data.is_none() && element.neighbourhood == 0));
     }
 }
@@ -1,31 +1,39 @@
 use crate::{
     bitfield::{BitField, DefaultBitField},
     bucket::Bucket,
-    {BiMap, DEFAULT_HASH_MAP_SIZE, MAX_LOAD_FACTOR},
+    pair_index::{self, DefaultPairIndex, PairIndex},
+    {buckets_for, BiMap, DEFAULT_HASH_MAP_SIZE, DEFAULT_LOAD_FACTOR, RESIZE_GROWTH_FACTOR},
 };
 
-use std::{cmp, collections::hash_map::RandomState, hash::BuildHasher, marker::PhantomData};
+use core::{
+    cmp,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
 
 /// A builder for the bimap. Allows for the parameters used to tune the BiMap to be configured.
 #[derive(Debug)]
-pub struct BiMapBuilder<LH, RH, B> {
+pub struct BiMapBuilder<LH, RH, B, I = DefaultPairIndex> {
     capacity: usize,
     left_hasher: LH,
     right_hasher: RH,
+    load_factor: f32,
+    growth_factor: usize,
+    max_len: Option<usize>,
     bit_field: PhantomData<B>,
+    pair_index: PhantomData<I>,
 }
 
+#[cfg(feature = "std")]
 impl Default for BiMapBuilder<RandomState, RandomState, DefaultBitField> {
     fn default() -> Self {
-        BiMapBuilder {
-            capacity: DEFAULT_HASH_MAP_SIZE,
-            left_hasher: Default::default(),
-            right_hasher: Default::default(),
-            bit_field: Default::default(),
-        }
+        BiMapBuilder::with_hashers(Default::default(), Default::default())
     }
 }
 
+#[cfg(feature = "std")]
 impl BiMapBuilder<RandomState, RandomState, DefaultBitField> {
     /// Create new builder, ready to be configured.
     ///
@@ -38,11 +46,40 @@ impl BiMapBuilder<RandomState, RandomState, DefaultBitField> {
     }
 }
 
-impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
+impl<LH: BuildHasher, RH: BuildHasher> BiMapBuilder<LH, RH, DefaultBitField> {
+    /// Create a new builder that uses the given hash builders, ready to be configured. This is the
+    /// only way to create a builder without the `std` feature, as there is no default hash builder
+    /// available.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    ///
+    /// type Hasher = BuildHasherDefault<DefaultHasher>;
+    /// let map: BiMap<String, String, Hasher, Hasher> =
+    ///     BiMapBuilder::with_hashers(Hasher::default(), Hasher::default()).finish();
+    /// ```
+    pub fn with_hashers(left_hasher: LH, right_hasher: RH) -> Self {
+        BiMapBuilder {
+            capacity: DEFAULT_HASH_MAP_SIZE,
+            left_hasher,
+            right_hasher,
+            load_factor: DEFAULT_LOAD_FACTOR,
+            growth_factor: RESIZE_GROWTH_FACTOR,
+            max_len: None,
+            bit_field: PhantomData,
+            pair_index: PhantomData,
+        }
+    }
+}
+
+impl<LH: BuildHasher, RH: BuildHasher, B: BitField, I: PairIndex> BiMapBuilder<LH, RH, B, I> {
     /// Sets the initial capacity of the bimap. It is not guaranteed that at least `capacity`
     /// elements can be inserted before the map needs to be resized, but it is likely. The only
     /// reason the map would need to be resized before that number of elements was inserted is due
-    /// to a large number of hash collisions.
+    /// to a large number of hash collisions. A non-empty map always has at least as many buckets as
+    /// its neighbourhood is wide, so very small capacities are rounded up to that.
     ///
     /// ```
     /// # use isomorphism::{BiMap, BiMapBuilder};
@@ -52,6 +89,74 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
         BiMapBuilder { capacity, ..self }
     }
 
+    /// Sets the load factor of the bimap - the maximum fraction of its buckets that may be full
+    /// before it is resized. Lower load factors use more memory, but keep the neighbourhoods
+    /// sparser so that fewer keys need to be displaced on insert. The default is a little over
+    /// 0.9. Panics if the load factor is not in the range `0.0 < factor <= 1.0`.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let map: BiMap<String, String> = BiMapBuilder::new()
+    ///             .capacity(100)
+    ///             .load_factor(0.5)
+    ///             .finish();
+    /// assert!(map.capacity() >= 100);
+    /// ```
+    pub fn load_factor(self, factor: f32) -> Self {
+        assert!(
+            factor > 0.0 && factor <= 1.0,
+            "load factor must be greater than 0 and at most 1, got {}",
+            factor
+        );
+        BiMapBuilder {
+            load_factor: factor,
+            ..self
+        }
+    }
+
+    /// Sets the growth factor of the bimap - how many times bigger it gets each time it has to be
+    /// resized. Larger growth factors mean fewer resizes for rapidly growing maps, at the cost of
+    /// more unused space. The number of buckets is always kept at a power of two, so growth
+    /// factors that are not themselves powers of two are rounded up when resizing. The default
+    /// is 2. Panics if the growth factor is less than 2.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let map: BiMap<String, String> = BiMapBuilder::new().growth_factor(4).finish();
+    /// ```
+    pub fn growth_factor(self, factor: usize) -> Self {
+        assert!(
+            factor >= 2,
+            "growth factor must be at least 2, got {}",
+            factor
+        );
+        BiMapBuilder {
+            growth_factor: factor,
+            ..self
+        }
+    }
+
+    /// Sets the most pairs that the bimap may hold. Once it is full, `insert_bounded` and
+    /// `try_insert` hand back pairs whose keys are both new rather than growing the map. Pairs
+    /// that replace an existing key are always allowed, as they never make the map any longer.
+    /// These two methods are the only ones that check the limit: `insert`, the entry API and the
+    /// bulk inserts (`extend`, `insert_all`, `append`, `merge_with` and `par_extend`) always add
+    /// their pairs, even past it. By default the map is unbounded.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let mut map: BiMap<&str, u32> = BiMapBuilder::new().max_len(2).finish();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    /// assert_eq!(Err(("Again", 9)), map.try_insert("Again", 9));
+    /// ```
+    pub fn max_len(self, limit: usize) -> Self {
+        BiMapBuilder {
+            max_len: Some(limit),
+            ..self
+        }
+    }
+
     /// Sets the hasher used for left values. By default, the hashmap will use the hashing
     /// algorithm used in the standard library hashmap, which is randomly generated and designed to
     /// be resistant to DoS attacks. Changing this hasher may lead to hash collisions and
@@ -65,12 +170,16 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     ///             .left_hasher(RandomState::new())
     ///             .finish();
     /// ```
-    pub fn left_hasher<LH2: BuildHasher>(self, hasher: LH2) -> BiMapBuilder<LH2, RH, B> {
+    pub fn left_hasher<LH2: BuildHasher>(self, hasher: LH2) -> BiMapBuilder<LH2, RH, B, I> {
         BiMapBuilder {
             capacity: self.capacity,
             left_hasher: hasher,
             right_hasher: self.right_hasher,
+            load_factor: self.load_factor,
+            growth_factor: self.growth_factor,
+            max_len: self.max_len,
             bit_field: self.bit_field,
+            pair_index: self.pair_index,
         }
     }
 
@@ -87,12 +196,41 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     ///             .right_hasher(RandomState::new())
     ///             .finish();
     /// ```
-    pub fn right_hasher<RH2: BuildHasher>(self, hasher: RH2) -> BiMapBuilder<LH, RH2, B> {
+    pub fn right_hasher<RH2: BuildHasher>(self, hasher: RH2) -> BiMapBuilder<LH, RH2, B, I> {
         BiMapBuilder {
             capacity: self.capacity,
             left_hasher: self.left_hasher,
             right_hasher: hasher,
+            load_factor: self.load_factor,
+            growth_factor: self.growth_factor,
+            max_len: self.max_len,
             bit_field: self.bit_field,
+            pair_index: self.pair_index,
+        }
+    }
+
+    /// Sets both hashers to copies of the ones used by an existing map, so that a map built from
+    /// this builder hashes every key exactly as `other` does. Given the same capacity and the same
+    /// sequence of inserts, the two maps will then store their keys in the same buckets.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let mut original: BiMap<&str, u32> = BiMap::new();
+    /// original.insert("Hello", 5);
+    ///
+    /// let mut copy = BiMapBuilder::new().clone_hashers_from(&original).finish();
+    /// copy.insert("Hello", 5);
+    /// assert_eq!(original, copy);
+    /// ```
+    pub fn clone_hashers_from<L, R>(self, other: &BiMap<L, R, LH, RH, B, I>) -> Self
+    where
+        LH: Clone,
+        RH: Clone,
+    {
+        BiMapBuilder {
+            left_hasher: other.left_hasher.clone(),
+            right_hasher: other.right_hasher.clone(),
+            ..self
         }
     }
 
@@ -101,7 +239,13 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     /// "neighbourhood" as its ideal location, regardless of hash collisions. The size of the
     /// neighbourhood - and therefore the maximum offset between a key's real location and its
     /// ideal location - is equal to the number of bits in this bitfield type. This can be tuned to
-    /// control the expected number of cache misses needed to do a lookup.
+    /// control the expected number of cache misses needed to do a lookup. Any unsigned integer
+    /// type from `u8` up to `u128` (including `usize`) can be used, as can `WideBitField<N>` for
+    /// neighbourhoods of `N * 64` bits, wider than any of the integer types.
+    ///
+    /// Narrow bitfields make each bucket smaller, but give each key fewer places to go. With a `u8`
+    /// bitfield, only eight keys can share a neighbourhood, so clustered hashes will often leave an
+    /// insert with nowhere to put its key, and the map will have to be resized early to make room.
     ///
     /// ```
     /// # use isomorphism::{BiMap, BiMapBuilder};
@@ -109,12 +253,46 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     ///             .bitfield::<u16>()
     ///             .finish();
     /// ```
-    pub fn bitfield<B2: BitField>(self) -> BiMapBuilder<LH, RH, B2> {
+    pub fn bitfield<B2: BitField>(self) -> BiMapBuilder<LH, RH, B2, I> {
         BiMapBuilder {
             capacity: self.capacity,
             left_hasher: self.left_hasher,
             right_hasher: self.right_hasher,
+            load_factor: self.load_factor,
+            growth_factor: self.growth_factor,
+            max_len: self.max_len,
             bit_field: PhantomData,
+            pair_index: self.pair_index,
+        }
+    }
+
+    /// Sets the integer type used to store positions within the bimap. Every bucket stores two
+    /// positions - that of the key it is paired with, and its own ideal position - so on 64 bit
+    /// platforms, using `u32` rather than the default `usize` makes each bucket smaller, which
+    /// saves memory and fits more buckets into each cache line. The map can then never have more
+    /// buckets than the type can count - 65536 for `u16`. Once it reaches that size it stops
+    /// growing, and keeps filling its buckets regardless of the load factor. When a pair finally
+    /// can't be fitted in, `insert_bounded` and `try_insert` hand it back, while `insert` and the
+    /// bulk inserts built on it panic with a capacity overflow. Asking for more room than that up
+    /// front with `capacity` or `reserve` stops at the limit, while `try_reserve` fails with
+    /// `TryReserveError::CapacityOverflow`. `u16`, `u32` and `usize` can be used.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let map: BiMap<String, String, _, _, _, u32> = BiMapBuilder::new()
+    ///             .pair_index::<u32>()
+    ///             .finish();
+    /// ```
+    pub fn pair_index<I2: PairIndex>(self) -> BiMapBuilder<LH, RH, B, I2> {
+        BiMapBuilder {
+            capacity: self.capacity,
+            left_hasher: self.left_hasher,
+            right_hasher: self.right_hasher,
+            load_factor: self.load_factor,
+            growth_factor: self.growth_factor,
+            max_len: self.max_len,
+            bit_field: self.bit_field,
+            pair_index: PhantomData,
         }
     }
 
@@ -125,17 +303,54 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     /// # use isomorphism::{BiMap, BiMapBuilder};
     /// let map: BiMap<String, String> = BiMapBuilder::new().finish();
     /// ```
-    pub fn finish<L, R>(self) -> BiMap<L, R, LH, RH, B> {
-        let capacity = match self.capacity {
-            0 => 0,
-            cap => (cmp::max(DEFAULT_HASH_MAP_SIZE, cap) as f32 * MAX_LOAD_FACTOR).ceil() as usize,
-        };
+    pub fn finish<L, R>(self) -> BiMap<L, R, LH, RH, B, I> {
+        let capacity = cmp::min(
+            buckets_for::<B>(self.capacity, self.load_factor),
+            pair_index::max_buckets::<I>(),
+        );
         BiMap {
             len: 0,
             left_data: Bucket::empty_vec(capacity),
             right_data: Bucket::empty_vec(capacity),
             left_hasher: self.left_hasher,
             right_hasher: self.right_hasher,
+            load_factor: self.load_factor,
+            growth_factor: self.growth_factor,
+            max_len: self.max_len,
+            pop_hint: 0,
         }
     }
+
+    /// Takes a completely configured builder, and creates a new `BiMap` with the specified
+    /// configurations, filled with the pairs from an iterator. The pairs are inserted in order, as
+    /// if by `extend`. The map starts with enough space for at least as many pairs as the
+    /// iterator's `size_hint` promises, or the configured capacity if that is larger.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    ///
+    /// type Hasher = BuildHasherDefault<DefaultHasher>;
+    /// let pairs = vec![("Hello", 5), ("World", 7)];
+    /// let map: BiMap<&str, u32, _, Hasher> = BiMapBuilder::new()
+    ///             .right_hasher(Hasher::default())
+    ///             .finish_from(pairs);
+    ///
+    /// assert_eq!(Some(&"World"), map.get_right(&7));
+    /// ```
+    pub fn finish_from<L, R, T>(self, iter: T) -> BiMap<L, R, LH, RH, B, I>
+    where
+        L: Hash + Eq,
+        R: Hash + Eq,
+        T: IntoIterator<Item = (L, R)>,
+    {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let capacity = cmp::max(self.capacity, lower);
+
+        let mut output = BiMapBuilder { capacity, ..self }.finish();
+        output.extend(iter);
+        output
+    }
 }
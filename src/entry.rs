@@ -0,0 +1,208 @@
+use crate::{
+    bitfield::BitField,
+    pair_index::{DefaultPairIndex, PairIndex},
+    BiMap,
+};
+
+use core::hash::{BuildHasher, Hash};
+
+/// A view into a single entry of a BiMap, looked up by its left key. Created by the `left_entry`
+/// method of `BiMap`.
+pub enum LeftEntry<'a, L, R, LH, RH, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    R: 'a,
+    LH: 'a,
+    RH: 'a,
+    B: 'a,
+{
+    /// The left key exists in the map.
+    Occupied(OccupiedLeftEntry<'a, L, R, LH, RH, B, I>),
+    /// The left key does not exist in the map.
+    Vacant(VacantLeftEntry<'a, L, R, LH, RH, B, I>),
+}
+
+/// A view into an entry of a BiMap whose left key exists in the map.
+pub struct OccupiedLeftEntry<'a, L, R, LH, RH, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    R: 'a,
+    LH: 'a,
+    RH: 'a,
+    B: 'a,
+{
+    map: &'a mut BiMap<L, R, LH, RH, B, I>,
+    /// The index of the left key within the left_data array.
+    index: usize,
+}
+
+/// A view into an entry of a BiMap whose left key does not exist in the map.
+pub struct VacantLeftEntry<'a, L, R, LH, RH, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    R: 'a,
+    LH: 'a,
+    RH: 'a,
+    B: 'a,
+{
+    map: &'a mut BiMap<L, R, LH, RH, B, I>,
+    left: L,
+    /// The hash of the left key.
+    hash: u64,
+}
+
+impl<'a, L, R, LH, RH, B, I> LeftEntry<'a, L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Returns a reference to the right value that the left key is paired with, inserting the
+    /// given right value first if the left key isn't in the map. Just like `insert`, if the right
+    /// value is already paired with another left key, that pair is removed from the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(&5, map.left_entry("Hello").or_insert(6));
+    /// assert_eq!(&7, map.left_entry("World").or_insert(7));
+    ///
+    /// // the pair ("Hello", 5) is evicted, because 5 is now paired with "Goodbye"
+    /// assert_eq!(&5, map.left_entry("Goodbye").or_insert(5));
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// ```
+    pub fn or_insert(self, default: R) -> &'a R {
+        match self {
+            LeftEntry::Occupied(entry) => entry.into_ref(),
+            LeftEntry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Returns a reference to the right value that the left key is paired with. If the left key
+    /// isn't in the map, `f` is called to create a right value, which is inserted first. Just like
+    /// `insert`, if the new right value is already paired with another left key, that pair is
+    /// removed from the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(&5, map.left_entry("Hello").or_insert_with(|| unreachable!()));
+    /// assert_eq!(&7, map.left_entry("World").or_insert_with(|| 7));
+    /// assert_eq!(Some(&"World"), map.get_right(&7));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> R>(self, f: F) -> &'a R {
+        match self {
+            LeftEntry::Occupied(entry) => entry.into_ref(),
+            LeftEntry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I: PairIndex> OccupiedLeftEntry<'a, L, R, LH, RH, B, I> {
+    pub(crate) fn new(map: &'a mut BiMap<L, R, LH, RH, B, I>, index: usize) -> Self {
+        OccupiedLeftEntry { map, index }
+    }
+
+    /// Gets a reference to the left key of this entry.
+    pub fn key(&self) -> &L {
+        let (left, ..) = self.map.left_data[self.index].data.as_ref().unwrap();
+        left
+    }
+
+    /// Gets a reference to the right value that the left key of this entry is paired with.
+    pub fn get(&self) -> &R {
+        let &(_, right_index, ..) = self.map.left_data[self.index].data.as_ref().unwrap();
+        let (right, ..) = self.map.right_data[right_index.to_usize()]
+            .data
+            .as_ref()
+            .unwrap();
+        right
+    }
+
+    /// Converts the entry into a reference to the right value that its left key is paired with,
+    /// which lives as long as the borrow of the map.
+    pub fn into_ref(self) -> &'a R {
+        let map: &'a BiMap<L, R, LH, RH, B, I> = self.map;
+        let &(_, right_index, ..) = map.left_data[self.index].data.as_ref().unwrap();
+        let (right, ..) = map.right_data[right_index.to_usize()]
+            .data
+            .as_ref()
+            .unwrap();
+        right
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I> OccupiedLeftEntry<'a, L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Removes both sides of this entry from the map, returning the right value.
+    pub fn remove(self) -> R {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ..
+        } = self.map;
+        let (_left, right) =
+            BiMap::<L, R, LH, RH, B, I>::remove_at(self.index, left_data, right_data, len);
+        right
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I> VacantLeftEntry<'a, L, R, LH, RH, B, I> {
+    pub(crate) fn new(map: &'a mut BiMap<L, R, LH, RH, B, I>, left: L, hash: u64) -> Self {
+        VacantLeftEntry { map, left, hash }
+    }
+
+    /// Gets a reference to the left key that would be used when inserting into this entry.
+    pub fn key(&self) -> &L {
+        &self.left
+    }
+
+    /// Takes ownership of the left key.
+    pub fn into_key(self) -> L {
+        self.left
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I> VacantLeftEntry<'a, L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Inserts the left key of this entry into the map, paired with a given right value, and
+    /// returns a reference to that right value. Just like `insert`, if the right value was already
+    /// paired with another left key, that pair is removed from the map.
+    pub fn insert(self, right: R) -> &'a R {
+        let VacantLeftEntry { map, left, hash } = self;
+
+        map.remove_right(&right);
+        let right_hash = BiMap::<L, R, LH, RH, B, I>::find_hash(&right, &map.right_hasher);
+        let index = map.insert_new(left, hash, right, right_hash);
+
+        let map: &'a BiMap<L, R, LH, RH, B, I> = map;
+        let &(_, right_index, ..) = map.left_data[index].data.as_ref().unwrap();
+        let (right, ..) = map.right_data[right_index.to_usize()]
+            .data
+            .as_ref()
+            .unwrap();
+        right
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    bitfield::{BitField, DefaultBitField},
+    pair_index::{DefaultPairIndex, PairIndex},
+    BiMap, DefaultHashBuilder, Iter,
+};
+
+use core::borrow::Borrow;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+
+/// A read-only `BiMap`, created by the `freeze` method of `BiMap`. It can only be looked up and
+/// iterated over, so a map that is built once and then only read can be shared between threads
+/// without any locking. It is `Sync` whenever the keys and hash builders are. Use `thaw` to get
+/// the mutable map back.
+///
+/// ```
+/// # use isomorphism::BiMap;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let mut map = BiMap::new();
+/// map.insert("Hello", 5);
+///
+/// let frozen = Arc::new(map.freeze());
+/// let reader = Arc::clone(&frozen);
+/// thread::spawn(move || assert_eq!(Some(&5), reader.get_left("Hello")))
+///     .join()
+///     .unwrap();
+/// assert_eq!(Some(&"Hello"), frozen.get_right(&5));
+/// ```
+#[derive(Clone)]
+pub struct FrozenBiMap<
+    L,
+    R,
+    LH = DefaultHashBuilder,
+    RH = DefaultHashBuilder,
+    B = DefaultBitField,
+    I = DefaultPairIndex,
+> {
+    map: BiMap<L, R, LH, RH, B, I>,
+}
+
+impl<L, R, LH, RH, B, I: PairIndex> FrozenBiMap<L, R, LH, RH, B, I> {
+    pub(crate) fn new(map: BiMap<L, R, LH, RH, B, I>) -> Self {
+        FrozenBiMap { map }
+    }
+
+    /// Unfreezes the map, giving back the `BiMap` it was created from.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let mut map = map.freeze().thaw();
+    /// map.insert("World", 7);
+    /// assert_eq!(2, map.len());
+    /// ```
+    pub fn thaw(self) -> BiMap<L, R, LH, RH, B, I> {
+        self.map
+    }
+
+    /// Returns the number of pairs in the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// assert_eq!(1, map.freeze().len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the map contains no pairs.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let map: BiMap<&str, u32> = BiMap::new();
+    /// assert!(map.freeze().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// An iterator visiting all key-value pairs in an arbitrary order. The iterator element is
+    /// type (&'a L, &'a R).
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let frozen = map.freeze();
+    /// assert_eq!(vec![(&"Hello", &5)], frozen.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R, B, I> {
+        self.map.iter()
+    }
+}
+
+impl<L, R, LH, RH, B, I> FrozenBiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Gets a key from the left of the map. Returns the value from the right of the map that
+    /// associates with this key, if it exists.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let frozen = map.freeze();
+    /// assert_eq!(Some(&5), frozen.get_left("Hello"));
+    /// assert_eq!(None, frozen.get_left("World"));
+    /// ```
+    pub fn get_left<'a, Q>(&'a self, left: &Q) -> Option<&'a R>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get_left(left)
+    }
+
+    /// Gets a key from the right of the map. Returns the value from the left of the map that
+    /// associates with this key, if it exists.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let frozen = map.freeze();
+    /// assert_eq!(Some(&"Hello"), frozen.get_right(&5));
+    /// assert_eq!(None, frozen.get_right(&7));
+    /// ```
+    pub fn get_right<'a, Q>(&'a self, right: &Q) -> Option<&'a L>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get_right(right)
+    }
+
+    /// Returns true if the map contains a pair with the given left key.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// assert!(map.freeze().contains_left("Hello"));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_left(left)
+    }
+
+    /// Returns true if the map contains a pair with the given right key.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// assert!(map.freeze().contains_right(&5));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_right(right)
+    }
+}
+
+impl<L, R, LH, RH, B, I> Debug for FrozenBiMap<L, R, LH, RH, B, I>
+where
+    L: Debug,
+    R: Debug,
+    I: PairIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.map.fmt_named("FrozenBiMap", f)
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I: PairIndex> IntoIterator for &'a FrozenBiMap<L, R, LH, RH, B, I> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R, B, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -1,35 +1,57 @@
-use crate::bucket::Bucket;
+use crate::{
+    bitfield::BitField,
+    bucket::Bucket,
+    pair_index::{DefaultPairIndex, PairIndex},
+    BiMap,
+};
 
-use std::iter::Iterator;
-use std::slice;
+use alloc::boxed::Box;
+use core::hash::{BuildHasher, Hash};
+use core::iter::{DoubleEndedIterator, ExactSizeIterator, FusedIterator, Iterator};
+use core::slice;
 
 /// An iterator over the pairs stored in a BiMap.
-pub struct Iter<'a, L, R, B>
+pub struct Iter<'a, L, R, B, I = DefaultPairIndex>
 where
     L: 'a,
     R: 'a,
     B: 'a,
 {
-    left_data: slice::Iter<'a, Bucket<L, usize, B>>,
-    right_data: &'a [Bucket<R, usize, B>],
+    left_data: slice::Iter<'a, Bucket<L, I, B>>,
+    right_data: &'a [Bucket<R, I, B>],
+    /// The number of pairs that have not yet been yielded.
+    remaining: usize,
 }
 
-impl<'a, L, R, B> Iter<'a, L, R, B> {
-    pub fn new(
-        left_data: slice::Iter<'a, Bucket<L, usize, B>>,
-        right_data: &'a [Bucket<R, usize, B>],
+impl<'a, L, R, B, I> Iter<'a, L, R, B, I> {
+    pub(crate) fn new(
+        left_data: slice::Iter<'a, Bucket<L, I, B>>,
+        right_data: &'a [Bucket<R, I, B>],
+        remaining: usize,
     ) -> Self {
         Iter {
             left_data,
             right_data,
+            remaining,
         }
     }
 }
 
-impl<'a, L, R, B> Iterator for Iter<'a, L, R, B>
+impl<'a, L, R, B, I> Clone for Iter<'a, L, R, B, I> {
+    fn clone(&self) -> Self {
+        Iter {
+            left_data: self.left_data.clone(),
+            right_data: self.right_data,
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, L, R, B, I> Iterator for Iter<'a, L, R, B, I>
 where
     L: 'a,
     R: 'a,
+    I: PairIndex,
 {
     type Item = (&'a L, &'a R);
 
@@ -37,35 +59,342 @@ where
         let &mut Iter {
             ref mut left_data,
             right_data,
+            ref mut remaining,
+        } = self;
+        let next = left_data
+            .filter_map(|bucket| bucket.data.as_ref())
+            .map(|&(ref key, value, ..)| {
+                (key, &right_data[value.to_usize()].data.as_ref().unwrap().0)
+            })
+            .next();
+        if next.is_some() {
+            *remaining -= 1;
+        }
+        next
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, L, R, B, I> DoubleEndedIterator for Iter<'a, L, R, B, I>
+where
+    L: 'a,
+    R: 'a,
+    I: PairIndex,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let &mut Iter {
+            ref mut left_data,
+            right_data,
+            ref mut remaining,
         } = self;
-        left_data
+        let next = left_data
+            .rev()
+            .filter_map(|bucket| bucket.data.as_ref())
+            .map(|&(ref key, value, ..)| {
+                (key, &right_data[value.to_usize()].data.as_ref().unwrap().0)
+            })
+            .next();
+        if next.is_some() {
+            *remaining -= 1;
+        }
+        next
+    }
+}
+
+impl<'a, L, R, B, I> ExactSizeIterator for Iter<'a, L, R, B, I>
+where
+    L: 'a,
+    R: 'a,
+    I: PairIndex,
+{
+}
+
+impl<'a, L, R, B, I> FusedIterator for Iter<'a, L, R, B, I>
+where
+    L: 'a,
+    R: 'a,
+    I: PairIndex,
+{
+}
+
+/// An iterator over the left keys stored in a BiMap.
+pub struct LeftKeys<'a, L, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    B: 'a,
+{
+    data: slice::Iter<'a, Bucket<L, I, B>>,
+    /// The number of keys that have not yet been yielded.
+    remaining: usize,
+}
+
+impl<'a, L, B, I> LeftKeys<'a, L, B, I> {
+    pub(crate) fn new(data: slice::Iter<'a, Bucket<L, I, B>>, remaining: usize) -> Self {
+        LeftKeys { data, remaining }
+    }
+}
+
+impl<'a, L, B, I> Clone for LeftKeys<'a, L, B, I> {
+    fn clone(&self) -> Self {
+        LeftKeys {
+            data: self.data.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, L, B, I> Iterator for LeftKeys<'a, L, B, I> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self
+            .data
+            .by_ref()
+            .filter_map(|bucket| bucket.data.as_ref())
+            .map(|(key, ..)| key)
+            .next();
+        if next.is_some() {
+            self.remaining -= 1;
+        }
+        next
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, L, B, I> DoubleEndedIterator for LeftKeys<'a, L, B, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let next = self
+            .data
+            .by_ref()
+            .rev()
+            .filter_map(|bucket| bucket.data.as_ref())
+            .map(|(key, ..)| key)
+            .next();
+        if next.is_some() {
+            self.remaining -= 1;
+        }
+        next
+    }
+}
+
+impl<'a, L, B, I> ExactSizeIterator for LeftKeys<'a, L, B, I> {}
+
+impl<'a, L, B, I> FusedIterator for LeftKeys<'a, L, B, I> {}
+
+/// An iterator over the right keys stored in a BiMap.
+pub struct RightKeys<'a, R, B, I = DefaultPairIndex>
+where
+    R: 'a,
+    B: 'a,
+{
+    data: slice::Iter<'a, Bucket<R, I, B>>,
+    /// The number of keys that have not yet been yielded.
+    remaining: usize,
+}
+
+impl<'a, R, B, I> RightKeys<'a, R, B, I> {
+    pub(crate) fn new(data: slice::Iter<'a, Bucket<R, I, B>>, remaining: usize) -> Self {
+        RightKeys { data, remaining }
+    }
+}
+
+impl<'a, R, B, I> Clone for RightKeys<'a, R, B, I> {
+    fn clone(&self) -> Self {
+        RightKeys {
+            data: self.data.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, R, B, I> Iterator for RightKeys<'a, R, B, I> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self
+            .data
+            .by_ref()
+            .filter_map(|bucket| bucket.data.as_ref())
+            .map(|(key, ..)| key)
+            .next();
+        if next.is_some() {
+            self.remaining -= 1;
+        }
+        next
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, R, B, I> DoubleEndedIterator for RightKeys<'a, R, B, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let next = self
+            .data
+            .by_ref()
+            .rev()
             .filter_map(|bucket| bucket.data.as_ref())
-            .map(|&(ref key, value, _)| (key, &right_data[value].data.as_ref().unwrap().0))
-            .next()
+            .map(|(key, ..)| key)
+            .next();
+        if next.is_some() {
+            self.remaining -= 1;
+        }
+        next
+    }
+}
+
+impl<'a, R, B, I> ExactSizeIterator for RightKeys<'a, R, B, I> {}
+
+impl<'a, R, B, I> FusedIterator for RightKeys<'a, R, B, I> {}
+
+/// The old name for `LeftKeys`.
+#[deprecated(note = "renamed to `LeftKeys`")]
+pub type LeftValues<'a, L, B, I = DefaultPairIndex> = LeftKeys<'a, L, B, I>;
+
+/// The old name for `RightKeys`.
+#[deprecated(note = "renamed to `RightKeys`")]
+pub type RightValues<'a, R, B, I = DefaultPairIndex> = RightKeys<'a, R, B, I>;
+
+/// A cursor over the pairs stored in a BiMap, which allows their right keys to be replaced. Created
+/// by the `iter_mut_right` method of `BiMap`.
+pub struct IterMutRight<'a, L, R, LH, RH, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    R: 'a,
+    LH: 'a,
+    RH: 'a,
+    B: 'a,
+    I: 'a,
+{
+    map: &'a mut BiMap<L, R, LH, RH, B, I>,
+    /// The index of the next left bucket to be checked.
+    index: usize,
+}
+
+impl<'a, L, R, LH, RH, B, I> IterMutRight<'a, L, R, LH, RH, B, I> {
+    pub(crate) fn new(map: &'a mut BiMap<L, R, LH, RH, B, I>) -> Self {
+        IterMutRight { map, index: 0 }
+    }
+
+    /// Moves on to the next pair in the map, returning a slot that can be used to read it or to
+    /// replace its right key, or `None` once every pair has been visited. Replacing a right key
+    /// never moves a left key, so every pair is visited exactly once.
+    // each slot borrows from the cursor, which `Iterator` can't express
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<RightSlot<'_, L, R, LH, RH, B, I>> {
+        let left_data = &self.map.left_data;
+        let left_index = (self.index..left_data.len()).find(|&i| left_data[i].data.is_some())?;
+        self.index = left_index + 1;
+        Some(RightSlot {
+            map: &mut *self.map,
+            left_index,
+        })
+    }
+}
+
+/// A single pair within a BiMap, whose right key can be replaced. Created by `IterMutRight`.
+pub struct RightSlot<'a, L, R, LH, RH, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    R: 'a,
+    LH: 'a,
+    RH: 'a,
+    B: 'a,
+    I: 'a,
+{
+    map: &'a mut BiMap<L, R, LH, RH, B, I>,
+    left_index: usize,
+}
+
+impl<'a, L, R, LH, RH, B, I: PairIndex> RightSlot<'a, L, R, LH, RH, B, I> {
+    /// Returns the left key of the pair.
+    pub fn left(&self) -> &L {
+        &self.map.left_data[self.left_index].data.as_ref().unwrap().0
+    }
+
+    /// Returns the right key of the pair.
+    pub fn right(&self) -> &R {
+        let right_index = self.map.left_data[self.left_index]
+            .data
+            .as_ref()
+            .unwrap()
+            .1
+            .to_usize();
+        &self.map.right_data[right_index].data.as_ref().unwrap().0
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I> RightSlot<'a, L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Replaces the right key of the pair, returning the old one. If its hash has changed, the key
+    /// is moved to wherever it now belongs. Since the map is being walked over, this can't evict
+    /// other pairs or resize the map like `replace_right_of` does. Instead, if the new right key
+    /// is already paired with a different left key, or there is no room for it without resizing,
+    /// the map is left unchanged and the new key is returned as an error.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// let mut slots = map.iter_mut_right();
+    /// while let Some(slot) = slots.next() {
+    ///     if *slot.left() == "Hello" {
+    ///         assert_eq!(Err(7), slot.set(7));
+    ///     }
+    /// }
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// ```
+    pub fn set(self, new_right: R) -> Result<R, R> {
+        self.map.try_replace_right_at(self.left_index, new_right)
     }
 }
 
 /// An owning iterator over the pairs stored in a BiMap.
-pub struct IntoIter<L, R, B> {
-    left_data: Box<[Bucket<L, usize, B>]>,
-    right_data: Box<[Bucket<R, usize, B>]>,
+pub struct IntoIter<L, R, B, I = DefaultPairIndex> {
+    left_data: Box<[Bucket<L, I, B>]>,
+    right_data: Box<[Bucket<R, I, B>]>,
+    /// The index of the next bucket to be checked from the front.
     index: usize,
+    /// One past the index of the next bucket to be checked from the back.
+    back_index: usize,
+    /// The number of pairs that have not yet been yielded.
+    remaining: usize,
 }
 
-impl<L, R, B> IntoIter<L, R, B> {
+impl<L, R, B, I> IntoIter<L, R, B, I> {
     pub(crate) fn new(
-        left_data: Box<[Bucket<L, usize, B>]>,
-        right_data: Box<[Bucket<R, usize, B>]>,
+        left_data: Box<[Bucket<L, I, B>]>,
+        right_data: Box<[Bucket<R, I, B>]>,
+        remaining: usize,
     ) -> Self {
+        let back_index = left_data.len();
         IntoIter {
             left_data,
             right_data,
             index: 0,
+            back_index,
+            remaining,
         }
     }
 }
 
-impl<L, R, B> Iterator for IntoIter<L, R, B> {
+impl<L, R, B, I: PairIndex> Iterator for IntoIter<L, R, B, I> {
     type Item = (L, R);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -73,19 +402,216 @@ impl<L, R, B> Iterator for IntoIter<L, R, B> {
             ref mut left_data,
             ref mut right_data,
             ref mut index,
+            back_index,
+            ref mut remaining,
         } = self;
 
         loop {
-            if *index >= left_data.len() {
+            if *index >= back_index {
                 break None;
             }
             if left_data[*index].data.is_some() {
                 let (left, right_index, ..) = left_data[*index].data.take().unwrap();
-                let (right, ..) = right_data[right_index].data.take().unwrap();
+                let (right, ..) = right_data[right_index.to_usize()].data.take().unwrap();
+                *index += 1;
+                *remaining -= 1;
+                break Some((left, right));
+            }
+            *index += 1;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<L, R, B, I: PairIndex> DoubleEndedIterator for IntoIter<L, R, B, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let &mut IntoIter {
+            ref mut left_data,
+            ref mut right_data,
+            index,
+            ref mut back_index,
+            ref mut remaining,
+        } = self;
+
+        loop {
+            if *back_index <= index {
+                break None;
+            }
+            *back_index -= 1;
+            if left_data[*back_index].data.is_some() {
+                let (left, right_index, ..) = left_data[*back_index].data.take().unwrap();
+                let (right, ..) = right_data[right_index.to_usize()].data.take().unwrap();
+                *remaining -= 1;
+                break Some((left, right));
+            }
+        }
+    }
+}
+
+impl<L, R, B, I: PairIndex> ExactSizeIterator for IntoIter<L, R, B, I> {}
+
+impl<L, R, B, I: PairIndex> FusedIterator for IntoIter<L, R, B, I> {}
+
+/// An owning iterator over the left keys stored in a BiMap. The right key paired with each left
+/// key is dropped as the left key is yielded.
+pub struct IntoLeft<L, R, B, I = DefaultPairIndex> {
+    inner: IntoIter<L, R, B, I>,
+}
+
+impl<L, R, B, I> IntoLeft<L, R, B, I> {
+    pub(crate) fn new(inner: IntoIter<L, R, B, I>) -> Self {
+        IntoLeft { inner }
+    }
+}
+
+impl<L, R, B, I: PairIndex> Iterator for IntoLeft<L, R, B, I> {
+    type Item = L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(left, _)| left)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<L, R, B, I: PairIndex> DoubleEndedIterator for IntoLeft<L, R, B, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(left, _)| left)
+    }
+}
+
+impl<L, R, B, I: PairIndex> ExactSizeIterator for IntoLeft<L, R, B, I> {}
+
+impl<L, R, B, I: PairIndex> FusedIterator for IntoLeft<L, R, B, I> {}
+
+/// An owning iterator over the right keys stored in a BiMap. The left key paired with each right
+/// key is dropped as the right key is yielded.
+pub struct IntoRight<L, R, B, I = DefaultPairIndex> {
+    inner: IntoIter<L, R, B, I>,
+}
+
+impl<L, R, B, I> IntoRight<L, R, B, I> {
+    pub(crate) fn new(inner: IntoIter<L, R, B, I>) -> Self {
+        IntoRight { inner }
+    }
+}
+
+impl<L, R, B, I: PairIndex> Iterator for IntoRight<L, R, B, I> {
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, right)| right)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<L, R, B, I: PairIndex> DoubleEndedIterator for IntoRight<L, R, B, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, right)| right)
+    }
+}
+
+impl<L, R, B, I: PairIndex> ExactSizeIterator for IntoRight<L, R, B, I> {}
+
+impl<L, R, B, I: PairIndex> FusedIterator for IntoRight<L, R, B, I> {}
+
+/// A draining iterator over the pairs stored in a BiMap. When dropped, any pairs that have not yet
+/// been yielded are removed from the map. Each pair is fully removed from the map as it is
+/// yielded, so if the iterator is leaked the map keeps the pairs that were not yet yielded.
+pub struct Drain<'a, L, R, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    R: 'a,
+    B: BitField + 'a,
+{
+    left_data: &'a mut [Bucket<L, I, B>],
+    right_data: &'a mut [Bucket<R, I, B>],
+    len: &'a mut usize,
+    index: usize,
+}
+
+impl<'a, L, R, B: BitField, I> Drain<'a, L, R, B, I> {
+    pub(crate) fn new(
+        left_data: &'a mut [Bucket<L, I, B>],
+        right_data: &'a mut [Bucket<R, I, B>],
+        len: &'a mut usize,
+    ) -> Self {
+        Drain {
+            left_data,
+            right_data,
+            len,
+            index: 0,
+        }
+    }
+}
+
+/// Clears the neighbourhood bit that records the bucket at `actual_index` as being in use by a key
+/// that hashed to `ideal_index`.
+fn unmark<K, I, B: BitField>(
+    ideal_index: usize,
+    actual_index: usize,
+    data: &mut [Bucket<K, I, B>],
+) {
+    let offset = (data.len() + actual_index - ideal_index) & (data.len() - 1);
+    data[ideal_index].neighbourhood = data[ideal_index].neighbourhood & B::zero_at(offset);
+}
+
+impl<'a, L, R, B: BitField, I: PairIndex> Iterator for Drain<'a, L, R, B, I> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &mut Drain {
+            ref mut left_data,
+            ref mut right_data,
+            ref mut len,
+            ref mut index,
+        } = self;
+
+        loop {
+            if *index >= left_data.len() {
+                break None;
+            }
+            if let Some((left, right_index, ideal_left, _)) = left_data[*index].data.take() {
+                unmark(ideal_left.to_usize(), *index, left_data);
+                let right_index = right_index.to_usize();
+                let (right, _, ideal_right, _) = right_data[right_index].data.take().unwrap();
+                unmark(ideal_right.to_usize(), right_index, right_data);
+                **len -= 1;
                 *index += 1;
                 break Some((left, right));
             }
             *index += 1;
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (*self.len, Some(*self.len))
+    }
+}
+
+impl<'a, L, R, B: BitField, I: PairIndex> ExactSizeIterator for Drain<'a, L, R, B, I> {}
+
+impl<'a, L, R, B: BitField, I: PairIndex> FusedIterator for Drain<'a, L, R, B, I> {}
+
+impl<'a, L, R, B: BitField, I> Drop for Drain<'a, L, R, B, I> {
+    fn drop(&mut self) {
+        let empty = B::empty();
+        for bucket in self.left_data.iter_mut() {
+            bucket.data = None;
+            bucket.neighbourhood = empty;
+        }
+        for bucket in self.right_data.iter_mut() {
+            bucket.data = None;
+            bucket.neighbourhood = empty;
+        }
+        *self.len = 0;
+    }
 }
@@ -7,6 +7,15 @@
 //! The difference between a "value" in a hashmap and a "right key" in a `BiMap` is that the right
 //! key must be hashable and comparable, and that duplicate right keys cannot exist within the
 //! bimap, even if they have different left keys mapping to them.
+//!
+//! The `std` feature is enabled by default. Without it the crate is `no_std`, and only needs the
+//! `alloc` crate. There is no randomly seeded hash builder outside of the standard library, so in
+//! that case the hash builders must be given to `BiMapBuilder::with_hashers`, and the methods that
+//! rely on `RandomState` or `HashMap` (such as `BiMap::new` and the `bimap!` macro) are
+//! unavailable.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 #[cfg(test)]
 #[macro_use]
@@ -17,54 +26,176 @@ extern crate serde;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 pub mod bitfield;
 mod bucket;
 mod builder;
+mod entry;
+mod frozen;
 mod iterator;
+pub mod pair_index;
+#[cfg(feature = "rayon")]
+mod par_iter;
 
 use bitfield::{BitField, DefaultBitField};
-use bucket::Bucket;
+pub use bucket::Bucket;
 pub use builder::BiMapBuilder;
-pub use iterator::{IntoIter, Iter};
+pub use entry::{LeftEntry, OccupiedLeftEntry, VacantLeftEntry};
+pub use frozen::FrozenBiMap;
+#[allow(deprecated)]
+pub use iterator::{
+    Drain, IntoIter, IntoLeft, IntoRight, Iter, IterMutRight, LeftKeys, LeftValues, RightKeys,
+    RightSlot, RightValues,
+};
+use pair_index::{DefaultPairIndex, PairIndex};
+#[cfg(feature = "rayon")]
+pub use par_iter::ParIter;
 
-use std::borrow::Borrow;
+use alloc::boxed::Box;
+use alloc::vec::{self, Vec};
+use core::borrow::Borrow;
+use core::cmp;
+use core::error::Error;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+use core::iter::{Extend, FromIterator};
+use core::mem;
+use core::ops::Index;
+#[cfg(feature = "std")]
 use std::collections::hash_map::RandomState;
-use std::fmt::{self, Debug};
-use std::hash::{BuildHasher, Hash, Hasher};
-use std::iter::{self, Extend, FromIterator};
-use std::mem;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::convert::TryFrom;
 
 pub(crate) const DEFAULT_HASH_MAP_SIZE: usize = 32;
-const RESIZE_GROWTH_FACTOR: usize = 2;
+pub(crate) const RESIZE_GROWTH_FACTOR: usize = 2;
+
+/// The default maximum fraction of the buckets that may be full before the map is resized.
+pub(crate) const DEFAULT_LOAD_FACTOR: f32 = 1.0 / 1.1;
+
+/// The smallest number of buckets a non-empty table may have. A neighbourhood must never be wider
+/// than the table it lives in, or probing past the end of it would wrap around onto buckets that
+/// were already probed.
+pub(crate) fn min_buckets<B: BitField>() -> usize {
+    cmp::max(DEFAULT_HASH_MAP_SIZE, B::size().next_power_of_two())
+}
+
+/// Finds the number of buckets needed to hold a given number of pairs without exceeding the given
+/// load factor. This is always a power of two, so that hash values can be turned into indexes with
+/// a bitmask rather than a division, and is never less than `min_buckets` unless it is zero.
+pub(crate) fn buckets_for<B: BitField>(pairs: usize, load_factor: f32) -> usize {
+    checked_buckets_for::<B>(pairs, load_factor).expect("capacity overflow")
+}
+
+/// The same as `buckets_for`, but returns `None` instead of panicking if the number of buckets
+/// needed does not fit in a `usize`.
+fn checked_buckets_for<B: BitField>(pairs: usize, load_factor: f32) -> Option<usize> {
+    match pairs {
+        0 => Some(0),
+        pairs => {
+            let buckets = cmp::max(DEFAULT_HASH_MAP_SIZE, pairs) as f32 / load_factor;
+            if buckets >= usize::MAX as f32 {
+                None
+            } else {
+                // `f32::ceil` isn't available without std, but truncating then rounding up works
+                // just as well for positive numbers
+                let truncated = buckets as usize;
+                let buckets = if (truncated as f32) < buckets {
+                    truncated + 1
+                } else {
+                    truncated
+                };
+                buckets
+                    .checked_next_power_of_two()
+                    .map(|buckets| cmp::max(buckets, min_buckets::<B>()))
+            }
+        }
+    }
+}
+
+/// How far the keys in a `BiMap` have been displaced from their ideal positions. Created by the
+/// `probe_stats` method of `BiMap`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProbeStats {
+    /// The largest offset of any left key from its ideal position.
+    pub left_max: usize,
+    /// The average offset of the left keys from their ideal positions.
+    pub left_mean: f32,
+    /// The largest offset of any right key from its ideal position.
+    pub right_max: usize,
+    /// The average offset of the right keys from their ideal positions.
+    pub right_mean: f32,
+}
+
+/// Which of two conflicting pairs to keep when merging maps with `BiMap::merge_with`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keep {
+    /// Keep the pair that is already in the map, discarding the incoming pair.
+    KeepSelf,
+    /// Keep the incoming pair, evicting the pair that is already in the map.
+    KeepOther,
+}
+
+/// The hash builder used by a `BiMap` when none is specified. This is the standard library's
+/// randomly seeded `RandomState`.
+#[cfg(feature = "std")]
+pub type DefaultHashBuilder = RandomState;
 
-// left as a fraction to avoid floating point multiplication and division where it isn't needed
-pub(crate) const MAX_LOAD_FACTOR: f32 = 1.1;
+/// The hash builder used by a `BiMap` when none is specified. Without the `std` feature there is
+/// no sensible default, so this type can't be constructed, and the hash builders must be named
+/// explicitly and given to `BiMapBuilder::with_hashers`.
+#[cfg(not(feature = "std"))]
+#[derive(Clone, Copy, Debug)]
+pub enum DefaultHashBuilder {}
 
 /// The two way hashmap itself. See the crate level documentation for more information. Uses
 /// hopscotch hashing internally.
 ///
 /// L and R are the left and right types being mapped to eachother. LH and RH are the hash builders
-/// used to hash the left keys and right keys. B is the bitfield used to store neighbourhoods.
-#[derive(Clone)]
-pub struct BiMap<L, R, LH = RandomState, RH = RandomState, B = DefaultBitField> {
+/// used to hash the left keys and right keys. B is the bitfield used to store neighbourhoods, and
+/// I is the integer type used to store positions within the map.
+pub struct BiMap<
+    L,
+    R,
+    LH = DefaultHashBuilder,
+    RH = DefaultHashBuilder,
+    B = DefaultBitField,
+    I = DefaultPairIndex,
+> {
     /// The number of pairs inside the map
     len: usize,
     /// All of the left keys, and the locations of their pairs within the right_data array.
-    left_data: Box<[Bucket<L, usize, B>]>,
+    left_data: Box<[Bucket<L, I, B>]>,
     /// All of the right keys, and the locations of their pairs within the left_data array.
-    right_data: Box<[Bucket<R, usize, B>]>,
+    right_data: Box<[Bucket<R, I, B>]>,
     /// Used to generate hash values for the left keys
     left_hasher: LH,
     /// Used to generate hash values for the right keys
     right_hasher: RH,
+    /// The maximum fraction of the buckets that may be full before the map is resized
+    load_factor: f32,
+    /// How many times bigger the map gets each time it has to be resized
+    growth_factor: usize,
+    /// The most pairs the map may hold, if it is bounded
+    max_len: Option<usize>,
+    /// Where `pop` should start looking for a full bucket. This is only a hint, so it doesn't
+    /// matter if it is out of date.
+    pop_hint: usize,
 }
 
+#[cfg(feature = "std")]
 impl<L, R> Default for BiMap<L, R> {
     fn default() -> Self {
         BiMapBuilder::new().finish()
     }
 }
 
+#[cfg(feature = "std")]
 impl<L, R> BiMap<L, R> {
     /// Creates a new empty BiMap.
     ///
@@ -75,9 +206,39 @@ impl<L, R> BiMap<L, R> {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Creates a new empty BiMap with space for at least `capacity` pairs. See the `capacity`
+    /// method of `BiMapBuilder` for more information.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let map: BiMap<u64, char> = BiMap::with_capacity(1024);
+    /// assert!(map.capacity() >= 1024);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        BiMapBuilder::new().capacity(capacity).finish()
+    }
+}
+
+impl<L, R, LH: BuildHasher, RH: BuildHasher> BiMap<L, R, LH, RH> {
+    /// Creates a new empty BiMap that uses the given hash builders. This is a shortcut for
+    /// `BiMapBuilder::with_hashers(left_hasher, right_hasher).finish()`, and the same as
+    /// `HashMap::with_hasher` in the standard library.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut map = BiMap::with_hashers(RandomState::new(), RandomState::new());
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn with_hashers(left_hasher: LH, right_hasher: RH) -> Self {
+        BiMapBuilder::with_hashers(left_hasher, right_hasher).finish()
+    }
 }
 
-impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
+impl<L, R, LH, RH, B, I: PairIndex> BiMap<L, R, LH, RH, B, I> {
     /// Returns a lower bound on the number of elements that this hashmap can hold without needing
     /// to be resized.
     ///
@@ -88,7 +249,99 @@ impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
     /// assert!(capacity >= 0);
     /// ```
     pub fn capacity(&self) -> usize {
-        (self.left_data.len() as f32 / MAX_LOAD_FACTOR).floor() as usize
+        // truncation rounds down, as the capacity is never negative
+        (self.left_data.len() as f32 * self.load_factor) as usize
+    }
+
+    /// Returns the number of buckets on each side of the map. Unlike `capacity`, this includes the
+    /// buckets that the load factor keeps free, so it is always either zero or a power of two that
+    /// is at least as large as `capacity`.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let map: BiMap<String, String> = BiMapBuilder::new()
+    ///             .capacity(100)
+    ///             .load_factor(0.5)
+    ///             .finish();
+    /// assert_eq!(256, map.raw_capacity());
+    /// assert_eq!(128, map.capacity());
+    /// ```
+    pub fn raw_capacity(&self) -> usize {
+        debug_assert_eq!(self.left_data.len(), self.right_data.len());
+        self.left_data.len()
+    }
+
+    /// Returns the number of buckets on each side of a map built with room for `pairs` pairs and
+    /// the default load factor - that is, the `raw_capacity` of a map from `with_capacity(pairs)`,
+    /// or from a builder given `capacity(pairs)`. This depends on the bitfield and pair index
+    /// types, so it is an associated function of the map type rather than a free function. It is
+    /// never more than the pair index type can count. Panics with a capacity overflow if that many
+    /// buckets can't be counted in a `usize`.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let buckets = BiMap::<u32, char>::capacity_for(100);
+    /// assert_eq!(128, buckets);
+    ///
+    /// let map: BiMap<u32, char> = BiMapBuilder::new().capacity(100).finish();
+    /// assert_eq!(buckets, map.raw_capacity());
+    /// ```
+    pub fn capacity_for(pairs: usize) -> usize
+    where
+        B: BitField,
+    {
+        cmp::min(
+            buckets_for::<B>(pairs, DEFAULT_LOAD_FACTOR),
+            pair_index::max_buckets::<I>(),
+        )
+    }
+
+    /// Returns the buckets that make up the left side of the map, for inspecting its layout. The
+    /// position of each bucket, and the pair indices stored within them, are implementation
+    /// details: they are only meaningful until the map is next modified, and may differ between
+    /// versions of this crate.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let bucket = map.left_buckets().iter().find(|b| b.key().is_some()).unwrap();
+    /// let pair = bucket.pair_index().unwrap();
+    /// assert_eq!(Some(&5), map.right_buckets()[pair].key());
+    /// ```
+    pub fn left_buckets(&self) -> &[Bucket<L, I, B>] {
+        &self.left_data
+    }
+
+    /// Returns the buckets that make up the right side of the map, for inspecting its layout. The
+    /// same caveats apply as for `left_buckets`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let full = map.right_buckets().iter().filter(|b| b.key().is_some()).count();
+    /// assert_eq!(1, full);
+    /// ```
+    pub fn right_buckets(&self) -> &[Bucket<R, I, B>] {
+        &self.right_data
+    }
+
+    /// Returns the approximate number of bytes that the map has allocated on the heap for its
+    /// buckets, across both sides. This counts every bucket whether or not it is full, but does not
+    /// include any memory that the keys themselves own, such as the contents of a `String`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let small: BiMap<u8, u8> = BiMap::with_capacity(16);
+    /// let large: BiMap<u8, u8> = BiMap::with_capacity(4096);
+    /// assert!(small.memory_footprint() < large.memory_footprint());
+    /// ```
+    pub fn memory_footprint(&self) -> usize {
+        self.left_data.len() * mem::size_of::<Bucket<L, I, B>>()
+            + self.right_data.len() * mem::size_of::<Bucket<R, I, B>>()
     }
 
     /// Returns the number of pairs inside this hashmap. Each remove will decrement this count.
@@ -140,37 +393,275 @@ impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
     ///     println!("{} {}", left, right);
     /// }
     /// ```
-    pub fn iter(&self) -> Iter<L, R, B> {
+    pub fn iter(&self) -> Iter<'_, L, R, B, I> {
         self.into_iter()
     }
+
+    /// Returns every pair in the map, ordered by left key. Unlike `iter`, this has to collect all
+    /// of the pairs into a `Vec` and sort them before the first one can be returned, so it
+    /// allocates and takes O(n log n) time.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert(3, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// let pairs: Vec<_> = map.iter_sorted_by_left().collect();
+    /// assert_eq!(vec![(&1, &"a"), (&2, &"b"), (&3, &"c")], pairs);
+    /// ```
+    pub fn iter_sorted_by_left(&self) -> vec::IntoIter<(&L, &R)>
+    where
+        L: Ord,
+    {
+        let mut pairs: Vec<_> = self.iter().collect();
+        pairs.sort_unstable_by_key(|&(left, _)| left);
+        pairs.into_iter()
+    }
+
+    /// Returns the pair with the smallest left key, or `None` if the map is empty. Unlike picking
+    /// whichever pair `iter` happens to return first, this always gives the same pair for maps
+    /// with the same contents. This looks at every pair, so it takes O(n) time.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert(3, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// assert_eq!(Some((&1, &"a")), map.min_by_left());
+    /// ```
+    pub fn min_by_left(&self) -> Option<(&L, &R)>
+    where
+        L: Ord,
+    {
+        self.iter().min_by_key(|&(left, _)| left)
+    }
+
+    /// Returns the pair with the largest left key, or `None` if the map is empty. Like
+    /// `min_by_left`, this looks at every pair, so it takes O(n) time.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert(3, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// assert_eq!(Some((&3, &"c")), map.max_by_left());
+    /// ```
+    pub fn max_by_left(&self) -> Option<(&L, &R)>
+    where
+        L: Ord,
+    {
+        self.iter().max_by_key(|&(left, _)| left)
+    }
+
+    /// An iterator visiting all of the left keys in an arbitrary order, without looking at the
+    /// right keys they are paired with. The iterator element is type &'a L.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", "World");
+    /// map.insert("Hashmaps", "Are cool");
+    ///
+    /// let lefts: HashSet<_> = map.left_keys().collect();
+    /// assert!(lefts.contains(&"Hello"));
+    /// assert!(lefts.contains(&"Hashmaps"));
+    /// ```
+    pub fn left_keys(&self) -> LeftKeys<'_, L, B, I> {
+        LeftKeys::new(self.left_data.iter(), self.len)
+    }
+
+    /// An iterator visiting all of the right keys in an arbitrary order, without looking at the
+    /// left keys they are paired with. The iterator element is type &'a R.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", "World");
+    /// map.insert("Hashmaps", "Are cool");
+    ///
+    /// let rights: HashSet<_> = map.right_keys().collect();
+    /// assert!(rights.contains(&"World"));
+    /// assert!(rights.contains(&"Are cool"));
+    /// ```
+    pub fn right_keys(&self) -> RightKeys<'_, R, B, I> {
+        RightKeys::new(self.right_data.iter(), self.len)
+    }
+
+    /// Consumes the map, returning an iterator over its left keys in an arbitrary order. The right
+    /// keys are dropped along the way.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello".to_owned(), 5);
+    ///
+    /// let lefts: Vec<String> = map.into_left().collect();
+    /// assert_eq!(vec!["Hello".to_owned()], lefts);
+    /// ```
+    pub fn into_left(self) -> IntoLeft<L, R, B, I> {
+        IntoLeft::new(self.into_iter())
+    }
+
+    /// Consumes the map, returning an iterator over its right keys in an arbitrary order. The left
+    /// keys are dropped along the way.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5.to_string());
+    ///
+    /// let rights: Vec<String> = map.into_right().collect();
+    /// assert_eq!(vec!["5".to_owned()], rights);
+    /// ```
+    pub fn into_right(self) -> IntoRight<L, R, B, I> {
+        IntoRight::new(self.into_iter())
+    }
+
+    /// The old name for `left_keys`.
+    #[deprecated(note = "renamed to `left_keys`")]
+    pub fn left_values(&self) -> LeftKeys<'_, L, B, I> {
+        self.left_keys()
+    }
+
+    /// The old name for `right_keys`.
+    #[deprecated(note = "renamed to `right_keys`")]
+    pub fn right_values(&self) -> RightKeys<'_, R, B, I> {
+        self.right_keys()
+    }
+
+    /// Returns a reference to the hash builder used for the left keys.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// use std::collections::hash_map::RandomState;
+    /// use std::hash::BuildHasher;
+    ///
+    /// let hasher = RandomState::new();
+    /// let map: BiMap<String, String> = BiMapBuilder::new()
+    ///             .left_hasher(hasher.clone())
+    ///             .finish();
+    ///
+    /// assert_eq!(hasher.hash_one("Hello"), map.left_hasher().hash_one("Hello"));
+    /// ```
+    pub fn left_hasher(&self) -> &LH {
+        &self.left_hasher
+    }
+
+    /// Returns a reference to the hash builder used for the right keys.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// use std::hash::BuildHasherDefault;
+    /// use std::collections::hash_map::DefaultHasher;
+    ///
+    /// let map: BiMap<u32, u32, _, BuildHasherDefault<DefaultHasher>> = BiMapBuilder::new()
+    ///             .right_hasher(Default::default())
+    ///             .finish();
+    /// let hasher: &BuildHasherDefault<DefaultHasher> = map.right_hasher();
+    /// ```
+    pub fn right_hasher(&self) -> &RH {
+        &self.right_hasher
+    }
+
+    /// Freezes the map, making it read-only. See `FrozenBiMap` for more information.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let frozen = map.freeze();
+    /// assert_eq!(Some(&5), frozen.get_left("Hello"));
+    /// ```
+    pub fn freeze(self) -> FrozenBiMap<L, R, LH, RH, B, I> {
+        FrozenBiMap::new(self)
+    }
+
+    /// Swaps the two sides of the map, so that the left keys become right keys and the right keys
+    /// become left keys. The hashers are swapped along with them, so no keys need to be rehashed.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let inverted: BiMap<u32, &str> = map.invert();
+    /// assert_eq!(Some(&"Hello"), inverted.get_left(&5));
+    /// assert_eq!(Some(&5), inverted.get_right("Hello"));
+    /// ```
+    pub fn invert(self) -> BiMap<R, L, RH, LH, B, I> {
+        let BiMap {
+            len,
+            left_data,
+            right_data,
+            left_hasher,
+            right_hasher,
+            load_factor,
+            growth_factor,
+            max_len,
+            pop_hint,
+        } = self;
+
+        BiMap {
+            len,
+            left_data: right_data,
+            right_data: left_data,
+            left_hasher: right_hasher,
+            right_hasher: left_hasher,
+            load_factor,
+            growth_factor,
+            max_len,
+            pop_hint,
+        }
+    }
 }
 
-impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B>
+impl<L, R, LH, RH, B, I> BiMap<L, R, LH, RH, B, I>
 where
     L: Hash + Eq,
     R: Hash + Eq,
     LH: BuildHasher,
     RH: BuildHasher,
     B: BitField,
+    I: PairIndex,
 {
+    /// Finds the hash of a key. This is stored alongside the key so that it can be reused when
+    /// the key is moved to a new position.
+    fn find_hash<K: Hash, H: BuildHasher>(key: &K, hasher: &H) -> u64 {
+        hasher.hash_one(key)
+    }
+
+    /// Finds the ideal position of a key with a given hash within the hashmap.
+    fn ideal_index(hash: u64, len: usize) -> usize {
+        hash as usize & (len - 1)
+    }
+
     /// Finds the ideal position of a key within the hashmap.
     fn find_ideal_index<K: Hash, H: BuildHasher>(key: &K, hasher: &H, len: usize) -> usize {
-        let mut hasher = hasher.build_hasher();
-        key.hash(&mut hasher);
-        hasher.finish() as usize % len
+        Self::ideal_index(Self::find_hash(key, hasher), len)
     }
 
     /// Find the bitfield associated with an ideal hash index in a hashmap array, and mark a given
     /// index as full.
-    fn mark_as_full<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, usize, B>]) {
-        let offset = (data.len() + actual_index - ideal_index) % data.len();
+    fn mark_as_full<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, I, B>]) {
+        let offset = (data.len() + actual_index - ideal_index) & (data.len() - 1);
         data[ideal_index].neighbourhood = data[ideal_index].neighbourhood | B::one_at(offset);
     }
 
     /// Finds the bitflield associated with an ideal hash index in a hashmap array, and mark a
     /// given index as empty.
-    fn mark_as_empty<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, usize, B>]) {
-        let offset = (data.len() + actual_index - ideal_index) % data.len();
+    fn mark_as_empty<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, I, B>]) {
+        let offset = (data.len() + actual_index - ideal_index) & (data.len() - 1);
         data[ideal_index].neighbourhood = data[ideal_index].neighbourhood & B::zero_at(offset);
     }
 
@@ -180,93 +671,132 @@ where
     /// key that was going to be inserted. If this function returns successfully, it is guaranteed
     /// that the key is located at the index specified, but its matching value is not set to
     /// anything meaningful. This is the callers responsibility.
-    fn insert_one_sided<K: Hash, V, H: BuildHasher>(
+    fn insert_one_sided<K, V>(
         key: K,
-        key_data: &mut [Bucket<K, usize, B>],
-        value_data: &mut [Bucket<V, usize, B>],
-        hasher: &H,
+        hash: u64,
+        key_data: &mut [Bucket<K, I, B>],
+        value_data: &mut [Bucket<V, I, B>],
     ) -> Result<usize, K> {
         let len = key_data.len();
-        let ideal_index = Self::find_ideal_index(&key, hasher, len);
 
-        if key_data[ideal_index].neighbourhood.full() {
-            return Err(key);
-        }
+        // every time a key is booted out of its bucket to make room, the bucket it was booted out
+        // of is recorded here, along with the index of its value, so that it can either be
+        // re-paired with its value once it has been inserted elsewhere or be put back if the
+        // insert fails. This keeps the stack usage constant no matter how long the chain gets.
+        let mut displaced: Vec<(usize, usize)> = Vec::new();
+        let mut key = key;
+        let mut hash = hash;
+
+        let result = loop {
+            let ideal_index = Self::ideal_index(hash, len);
+
+            if key_data[ideal_index].neighbourhood.full() {
+                break Err(key);
+            }
+
+            let nearest = key_data[ideal_index..]
+                .iter()
+                .chain(key_data[..ideal_index].iter())
+                .enumerate()
+                .find(|&(_, bucket)| bucket.data.is_none())
+                .map(|(offset, _)| offset);
+            let offset = match nearest {
+                Some(offset) => offset,
+                // there is no free space
+                None => break Err(key),
+            };
 
-        let nearest = key_data[ideal_index..]
-            .iter()
-            .chain(key_data[..ideal_index].iter())
-            .enumerate()
-            .find(|&(_, bucket)| bucket.data.is_none())
-            .map(|(offset, _)| offset);
-        if let Some(offset) = nearest {
             // is this free space within the neighbourhood?
             if offset < B::size() {
                 // insert and we're done
-                let index = (offset + ideal_index) % len;
+                let index = (offset + ideal_index) & (len - 1);
                 Self::mark_as_full(ideal_index, index, key_data);
-                key_data[index].data = Some((key, usize::max_value(), ideal_index));
-                Ok(index)
-            } else {
-                // need to make room -> find a space, boot the old thing out to make room, insert,
-                // repeat
-                let max_offset = (ideal_index + B::size()) % len;
-                let nearest = (0..)
-                    .map(|i| (len + max_offset - i) % len)
-                    .take(B::size() - 1)
-                    .skip(1)
-                    .find(|&i| {
-                        let &(_, _, ideal) = key_data[i].data.as_ref().unwrap();
-                        // check if the bucket we're planning to displace is closer to the blank
-                        // space than we are, and make sure that it is close enough for us to move
-                        // into its spot (more complicated due to wrap around)
-                        if ideal > ideal_index {
-                            ideal - ideal_index < B::size()
-                        } else if ideal < ideal_index {
-                            ideal < max_offset && max_offset < ideal
-                        } else {
-                            false
-                        }
-                    });
-                if let Some(index) = nearest {
-                    // we've found a spot to insert into
-                    let (new_key, new_value, new_ideal) = key_data[index].data.take().unwrap();
-                    Self::mark_as_empty(new_ideal, index, key_data);
-                    key_data[index].data = Some((key, usize::max_value(), ideal_index));
-                    Self::mark_as_full(ideal_index, index, key_data);
-                    match Self::insert_one_sided(new_key, key_data, value_data, hasher) {
-                        Ok(new_key_index) => {
-                            // the replacement worked
-                            {
-                                let &mut (_, ref mut paired_key_index, _) =
-                                    value_data[new_value].data.as_mut().unwrap();
-                                *paired_key_index = new_key_index;
-                                let &mut (_, ref mut paired_value_index, _) =
-                                    key_data[new_key_index].data.as_mut().unwrap();
-                                *paired_value_index = new_value;
-                            }
-                            Self::mark_as_full(new_ideal, new_key_index, key_data);
+                key_data[index].data = Some((
+                    key,
+                    I::from_usize(I::max()),
+                    I::from_usize(ideal_index),
+                    hash,
+                ));
+                break Ok(index);
+            }
 
-                            // finish our insert and return
-                            Ok(index)
-                        }
-                        Err(new_key) => {
-                            // the replacement failed - undo our insert
-                            Self::mark_as_full(new_ideal, index, key_data);
-                            Self::mark_as_empty(ideal_index, index, key_data);
-                            let (key, _, _) = key_data[index].data.take().unwrap();
-                            key_data[index].data = Some((new_key, new_value, new_ideal));
-                            Err(key)
-                        }
+            // need to make room -> find a space, boot the old thing out to make room, insert,
+            // repeat
+            let max_offset = (ideal_index + B::size()) & (len - 1);
+            let nearest = (0..)
+                .map(|i| (len + max_offset - i) & (len - 1))
+                .take(B::size() - 1)
+                .skip(1)
+                .find(|&i| {
+                    let ideal = key_data[i].data.as_ref().unwrap().2.to_usize();
+                    // check if the bucket we're planning to displace is closer to the blank
+                    // space than we are, and make sure that it is close enough for us to move
+                    // into its spot (more complicated due to wrap around)
+                    if ideal > ideal_index {
+                        ideal - ideal_index < B::size()
+                    } else if ideal < ideal_index {
+                        ideal < max_offset && max_offset < ideal
+                    } else {
+                        false
                     }
-                } else {
-                    // no spot can be inserted into
-                    Err(key)
+                });
+            let index = match nearest {
+                Some(index) => index,
+                // no spot can be inserted into
+                None => break Err(key),
+            };
+
+            // we've found a spot to insert into
+            let (new_key, new_value, new_ideal, new_hash) = key_data[index].data.take().unwrap();
+            Self::mark_as_empty(new_ideal.to_usize(), index, key_data);
+            key_data[index].data = Some((
+                key,
+                I::from_usize(I::max()),
+                I::from_usize(ideal_index),
+                hash,
+            ));
+            Self::mark_as_full(ideal_index, index, key_data);
+
+            displaced.push((index, new_value.to_usize()));
+            key = new_key;
+            hash = new_hash;
+        };
+
+        match result {
+            Ok(mut new_key_index) => {
+                // the replacements worked - re-pair each displaced key with its value, starting
+                // from the end of the chain
+                while let Some((index, new_value)) = displaced.pop() {
+                    let &mut (_, ref mut paired_key_index, ..) =
+                        value_data[new_value].data.as_mut().unwrap();
+                    *paired_key_index = I::from_usize(new_key_index);
+                    let &mut (_, ref mut paired_value_index, ..) =
+                        key_data[new_key_index].data.as_mut().unwrap();
+                    *paired_value_index = I::from_usize(new_value);
+
+                    new_key_index = index;
                 }
+                Ok(new_key_index)
+            }
+            Err(mut new_key) => {
+                // a replacement failed - undo every insert in the chain, starting from the end
+                while let Some((index, new_value)) = displaced.pop() {
+                    let new_ideal = Self::ideal_index(hash, len);
+                    let (key, _, ideal_index, key_hash) = key_data[index].data.take().unwrap();
+                    Self::mark_as_full(new_ideal, index, key_data);
+                    Self::mark_as_empty(ideal_index.to_usize(), index, key_data);
+                    key_data[index].data = Some((
+                        new_key,
+                        I::from_usize(new_value),
+                        I::from_usize(new_ideal),
+                        hash,
+                    ));
+
+                    new_key = key;
+                    hash = key_hash;
+                }
+                Err(new_key)
             }
-        } else {
-            // there is no free space
-            Err(key)
         }
     }
 
@@ -290,482 +820,3064 @@ where
     /// // the hashmap.
     /// assert_eq!(None, map.get_right(&5));
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Every key with the same hash has to be stored in the same neighbourhood, so the map can
+    /// hold at most as many of them as the bitfield has bits, no matter how much it grows. Rather
+    /// than resizing forever, this panics if a key would be one too many. This can only happen
+    /// with a badly broken hasher, or keys chosen to attack one.
+    ///
+    /// A map whose pair index type is narrower than `usize` also panics with a capacity overflow
+    /// once it has as many buckets as that type can count, and the pair can't be fitted into them.
+    /// `insert_bounded` hands the pair back instead.
+    ///
+    /// This always inserts the pair, even if that takes the map past the `max_len` it was built
+    /// with. Use `insert_bounded` to respect the limit instead.
     pub fn insert(&mut self, left: L, right: R) -> (Option<R>, Option<L>) {
+        // each key is hashed once, and the hash is used both to evict old pairs and to insert
+        let left_hash = Self::find_hash(&left, &self.left_hasher);
+        let right_hash = Self::find_hash(&right, &self.right_hasher);
+        self.insert_hashed(left, left_hash, right, right_hash)
+    }
+
+    /// The same as `insert`, but for a pair whose keys have already been hashed with this map's
+    /// hashers, so that they don't need to be hashed again.
+    pub(crate) fn insert_hashed(
+        &mut self,
+        left: L,
+        left_hash: u64,
+        right: R,
+        right_hash: u64,
+    ) -> (Option<R>, Option<L>) {
         let output = {
             let &mut BiMap {
                 ref mut len,
                 ref mut left_data,
                 ref mut right_data,
-                ref left_hasher,
-                ref right_hasher,
+                ..
             } = self;
-            match Self::remove(&left, left_data, right_data, left_hasher, right_hasher, len) {
+            match Self::remove_hashed(&left, left_hash, left_data, right_data, len) {
                 Some((old_left, old_right)) => {
                     if old_right == right {
                         (Some(old_right), Some(old_left))
                     } else {
                         (
                             Some(old_right),
-                            Self::remove(
-                                &right,
-                                right_data,
-                                left_data,
-                                right_hasher,
-                                left_hasher,
-                                len,
-                            )
-                            .map(|(_key, value)| value),
+                            Self::remove_hashed(&right, right_hash, right_data, left_data, len)
+                                .map(|(_key, value)| value),
                         )
                     }
                 }
                 None => (
                     None,
-                    Self::remove(
-                        &right,
-                        right_data,
-                        left_data,
-                        right_hasher,
-                        left_hasher,
-                        len,
-                    )
-                    .map(|(_key, value)| value),
+                    Self::remove_hashed(&right, right_hash, right_data, left_data, len)
+                        .map(|(_key, value)| value),
                 ),
             }
         };
 
-        // attempt to insert, hold onto the keys if it fails
-        let failure: Option<(L, R)> = if MAX_LOAD_FACTOR * self.len as f32
-            >= self.left_data.len() as f32
-        {
-            Some((left, right))
-        } else {
-            let &mut BiMap {
-                ref mut left_data,
-                ref mut right_data,
-                ref left_hasher,
-                ref right_hasher,
-                ..
-            } = self;
-            match Self::insert_one_sided(left, left_data, right_data, left_hasher) {
-                Ok(left_index) => {
-                    match Self::insert_one_sided(right, right_data, left_data, right_hasher) {
-                        Ok(right_index) => {
-                            let &mut (_, ref mut paired_right_index, _) =
-                                left_data[left_index].data.as_mut().unwrap();
-                            *paired_right_index = right_index;
-
-                            let &mut (_, ref mut paired_left_index, _) =
-                                right_data[right_index].data.as_mut().unwrap();
-                            *paired_left_index = left_index;
-                            None
-                        }
-                        Err(right) => {
-                            let (left, _, left_ideal) = left_data[left_index].data.take().unwrap();
-                            Self::mark_as_empty(left_ideal, left_index, left_data);
-                            Some((left, right))
-                        }
-                    }
-                }
-                Err(left) => Some((left, right)),
-            }
-        };
-
-        if failure.is_none() {
-            self.len += 1;
-        }
-
-        if let Some((left, right)) = failure {
-            // resize, as we were unable to insert
-            self.len = 0;
-            let capacity = self.left_data.len() * RESIZE_GROWTH_FACTOR;
-            let old_left_data = mem::replace(&mut self.left_data, Bucket::empty_vec(capacity));
-            let old_right_data = mem::replace(&mut self.right_data, Bucket::empty_vec(capacity));
-
-            iter::once((left, right))
-                .chain(IntoIter::new(old_left_data, old_right_data))
-                .for_each(|(left, right)| {
-                    self.insert(left, right);
-                });
-        }
+        self.insert_new(left, left_hash, right, right_hash);
 
         output
     }
 
-    /// Looks up a key in the key_data section of the hashap, and if it exists returns it from the
-    /// value_data section of the hashap. Returns the value that is associated with the key, if it
-    /// exists.
-    fn get<'a, Q: ?Sized, K, V, KH>(
-        key: &Q,
-        key_data: &[Bucket<K, usize, B>],
-        value_data: &'a [Bucket<V, usize, B>],
-        key_hasher: &KH,
-    ) -> Option<&'a V>
-    where
-        Q: Hash + Eq,
-        K: Hash + Eq + Borrow<Q>,
-        KH: BuildHasher,
-    {
-        let len = key_data.len();
-        let ideal = Self::find_ideal_index(&key, key_hasher, len);
-
-        let neighbourhood = key_data[ideal].neighbourhood;
-        neighbourhood
-            .iter()
-            .filter_map(|offset| key_data[(ideal + offset) % len].data.as_ref())
-            .filter(|&&(ref candidate_key, ..)| candidate_key.borrow() == key)
-            .filter_map(|&(_, pair_index, _)| value_data[pair_index].data.as_ref())
-            .map(|&(ref value, ..)| value)
-            .next()
-    }
-
-    /// Removes a key from the key_data section of the hashmap, and removes the value from the
-    /// value_data section of the hashmap. Returns the value that is associated with the key, if it
-    /// exists.
-    fn remove<Q: ?Sized, K, V, KH, VH>(
-        key: &Q,
-        key_data: &mut [Bucket<K, usize, B>],
-        value_data: &mut [Bucket<V, usize, B>],
-        key_hasher: &KH,
-        value_hasher: &VH,
-        map_len: &mut usize,
-    ) -> Option<(K, V)>
-    where
-        Q: Hash + Eq,
-        K: Hash + Eq + Borrow<Q>,
-        V: Hash,
-        KH: BuildHasher,
-        VH: BuildHasher,
-    {
-        let len = key_data.len();
-        let index = Self::find_ideal_index(&key, key_hasher, len);
-
-        let neighbourhood = key_data[index].neighbourhood;
-        if let Some(offset) =
-            neighbourhood
-                .iter()
-                .find(|offset| match key_data[(index + offset) % len].data {
-                    Some((ref candidate_key, ..)) => candidate_key.borrow() == key,
-                    _ => false,
-                })
-        {
-            key_data[index].neighbourhood = neighbourhood & B::zero_at(offset);
-            let (key, value_index, _) = key_data[(index + offset) % len].data.take().unwrap();
-            let (value, ..) = value_data[value_index].data.take().unwrap();
-
-            let ideal_value_index = Self::find_ideal_index(&value, value_hasher, len);
-            let value_offset = (value_index + len - ideal_value_index) % len;
-
-            value_data[ideal_value_index].neighbourhood =
-                value_data[ideal_value_index].neighbourhood & B::zero_at(value_offset);
-
-            *map_len -= 1;
-
-            Some((key, value))
-        } else {
-            None
-        }
+    /// Inserts an (L, R) pair into the hashmap without first checking whether either key is already
+    /// in the map. This skips the lookups that `insert` does to evict old pairs, which can speed up
+    /// bulk loads of data that is already known to be free of duplicates.
+    ///
+    /// It is a logic error for `left` or `right` to already be in the map. This is checked in debug
+    /// builds, but not in release builds. The behavior resulting from such a logic error is not
+    /// specified, but will not result in undefined behavior: both copies of a key may be kept,
+    /// lookups may find either one, and the length may no longer match the number of distinct
+    /// keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::with_capacity(100);
+    /// for i in 0..100 {
+    ///     map.insert_unchecked(i, i * 2);
+    /// }
+    ///
+    /// assert_eq!(100, map.len());
+    /// assert_eq!(Some(&21), map.get_right(&42));
+    /// ```
+    pub fn insert_unchecked(&mut self, left: L, right: R) {
+        debug_assert!(
+            !self.contains_left(&left) && !self.contains_right(&right),
+            "insert_unchecked called with a key that is already in the map"
+        );
+        let left_hash = Self::find_hash(&left, &self.left_hasher);
+        let right_hash = Self::find_hash(&right, &self.right_hasher);
+        self.insert_new(left, left_hash, right, right_hash);
     }
 
-    /// Gets a key from the left of the hashmap. Returns the value from the right of the hashmap
-    /// that associates with this key, if it exists.
+    /// Inserts an (L, R) pair into the hashmap, but only if neither the left nor the right key
+    /// already exist within the map. If either of them do, the map already holds its `max_len`
+    /// pairs, or the map would need more buckets than its pair index type can count, the map is
+    /// left unchanged and the pair is handed back. The same happens if either key's neighbourhood
+    /// is already full of keys with exactly the same hash, which can only happen with a badly
+    /// broken hasher, or keys chosen to attack one.
     ///
     /// ```
     /// # use isomorphism::BiMap;
     /// let mut map = BiMap::new();
-    /// assert_eq!(None, map.get_left("Hello"));
     ///
-    /// map.insert("Hello", 5);
+    /// assert_eq!(Ok(()), map.try_insert("Hello", 5));
+    /// assert_eq!(Err(("Hello", 7)), map.try_insert("Hello", 7));
+    /// assert_eq!(Err(("World", 5)), map.try_insert("World", 5));
+    ///
+    /// assert_eq!(1, map.len());
     /// assert_eq!(Some(&5), map.get_left("Hello"));
     /// ```
-    pub fn get_left<'a, Q: ?Sized>(&'a self, left: &Q) -> Option<&'a R>
-    where
-        L: Borrow<Q>,
-        Q: Hash + Eq,
-    {
-        let &BiMap {
-            ref left_data,
-            ref right_data,
-            ref left_hasher,
-            ..
-        } = self;
-        Self::get(left, left_data, right_data, left_hasher)
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        let left_hash = Self::find_hash(&left, &self.left_hasher);
+        if self.at_max_len()
+            || Self::find_index(&left, left_hash, &self.left_data).is_some()
+            || self.contains_right(&right)
+        {
+            Err((left, right))
+        } else {
+            let right_hash = Self::find_hash(&right, &self.right_hasher);
+            self.try_insert_new(left, left_hash, right, right_hash)
+                .map(|_| ())
+        }
     }
 
-    /// Gets a key from the right of the hashmap. Returns the value from the left of the hashmap
-    /// that associates with this key, if it exists.
+    /// Inserts an (L, R) pair into the hashmap like `insert`, unless doing so would take the map
+    /// past one of its limits. If the map was built with a `max_len`, already holds that many
+    /// pairs, and neither key is in it yet, adding the pair would make the map too long. If the map
+    /// already has as many buckets as its pair index type can count, and the pair can't be fitted
+    /// into them, the map would have to grow too large. If a new key's neighbourhood is already
+    /// full of keys with exactly the same hash, no size of map could fit it. In every case, the map
+    /// is left unchanged and the pair is handed back. Pairs that replace an existing key never make
+    /// the map any longer, so they are never rejected for the first reason.
     ///
     /// ```
-    /// # use isomorphism::BiMap;
-    /// let mut map = BiMap::new();
-    /// assert_eq!(None, map.get_right(&5));
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let mut map: BiMap<&str, u32> = BiMapBuilder::new().max_len(1).finish();
     ///
-    /// map.insert("Hello", 5);
-    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// assert_eq!(Ok((None, None)), map.insert_bounded("Hello", 5));
+    /// assert_eq!(Err(("World", 7)), map.insert_bounded("World", 7));
+    /// assert_eq!(Ok((Some(5), None)), map.insert_bounded("Hello", 7));
+    /// assert_eq!(1, map.len());
     /// ```
-    pub fn get_right<'a, Q: ?Sized>(&'a self, right: &Q) -> Option<&'a L>
-    where
-        R: Borrow<Q>,
-        Q: Hash + Eq,
-    {
-        let &BiMap {
-            ref right_data,
-            ref left_data,
-            ref right_hasher,
-            ..
-        } = self;
-        Self::get(right, right_data, left_data, right_hasher)
+    pub fn insert_bounded(&mut self, left: L, right: R) -> Result<(Option<R>, Option<L>), (L, R)> {
+        let left_hash = Self::find_hash(&left, &self.left_hasher);
+        let right_hash = Self::find_hash(&right, &self.right_hasher);
+        let left_index = Self::find_index(&left, left_hash, &self.left_data);
+        let right_index = Self::find_index(&right, right_hash, &self.right_data);
+
+        match (left_index, right_index) {
+            (None, None) if self.at_max_len() => Err((left, right)),
+            (None, None) => self
+                .try_insert_new(left, left_hash, right, right_hash)
+                .map(|_| (None, None)),
+            // only one key is new, so try to fit it in without moving the other. If that fails, an
+            // ordinary insert has to grow the map to make room, unless no size of map could fit it
+            (Some(left_index), None) => {
+                let &mut BiMap {
+                    ref mut left_data,
+                    ref mut right_data,
+                    ..
+                } = self;
+                match Self::try_replace_paired(left_index, right, right_hash, left_data, right_data)
+                {
+                    Ok(old_right) => Ok((Some(old_right), None)),
+                    Err(right)
+                        if self.grown_capacity().is_some()
+                            && !Self::is_saturated(right_hash, &self.right_data) =>
+                    {
+                        Ok(self.insert_hashed(left, left_hash, right, right_hash))
+                    }
+                    Err(right) => Err((left, right)),
+                }
+            }
+            (None, Some(right_index)) => {
+                let &mut BiMap {
+                    ref mut left_data,
+                    ref mut right_data,
+                    ..
+                } = self;
+                match Self::try_replace_paired(right_index, left, left_hash, right_data, left_data)
+                {
+                    Ok(old_left) => Ok((None, Some(old_left))),
+                    Err(left)
+                        if self.grown_capacity().is_some()
+                            && !Self::is_saturated(left_hash, &self.left_data) =>
+                    {
+                        Ok(self.insert_hashed(left, left_hash, right, right_hash))
+                    }
+                    Err(left) => Err((left, right)),
+                }
+            }
+            // both keys are already in the map, so evicting their pairs frees a bucket within each
+            // of their neighbourhoods, and the new pair always fits without growing
+            (Some(_), Some(_)) => Ok(self.insert_hashed(left, left_hash, right, right_hash)),
+        }
     }
 
-    /// Removes a key from the left of the hashmap. Returns the value from the right of the hashmap
-    /// that was associated with this key, if it existed. Will remove both the left and right sides
-    /// of the pair, if it exists, meaning that `get_right` will no longer work for the value
-    /// associated with the key that is removed.
+    /// Replaces the left key `old` with `new`, keeping it paired with the same right key. Returns
+    /// true if the key was renamed, or false if the map was left unchanged because `old` is not in
+    /// the map or `new` already is.
     ///
     /// ```
     /// # use isomorphism::BiMap;
     /// let mut map = BiMap::new();
     /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
     ///
-    /// assert_eq!(Some(&5), map.get_left("Hello"));
-    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
-    ///
-    /// let old = map.remove_left("Hello");
-    /// assert_eq!(Some(5), old);
-    ///
+    /// assert!(map.rename_left(&"Hello", "Goodbye"));
+    /// assert_eq!(Some(&"Goodbye"), map.get_right(&5));
     /// assert_eq!(None, map.get_left("Hello"));
-    /// assert_eq!(None, map.get_right(&5));
+    ///
+    /// assert!(!map.rename_left(&"Hello", "Again"));
+    /// assert!(!map.rename_left(&"Goodbye", "World"));
+    /// assert_eq!(Some(&5), map.get_left("Goodbye"));
     /// ```
-    pub fn remove_left<Q: ?Sized>(&mut self, left: &Q) -> Option<R>
-    where
-        L: Borrow<Q>,
-        Q: Hash + Eq,
-    {
+    pub fn rename_left(&mut self, old: &L, new: L) -> bool {
+        let old_hash = Self::find_hash(old, &self.left_hasher);
+        let new_hash = Self::find_hash(&new, &self.left_hasher);
+        let old_index = match Self::find_index(old, old_hash, &self.left_data) {
+            Some(index) if Self::find_index(&new, new_hash, &self.left_data).is_none() => index,
+            _ => return false,
+        };
+
         let &mut BiMap {
             ref mut len,
             ref mut left_data,
             ref mut right_data,
-            ref left_hasher,
-            ref right_hasher,
+            ..
         } = self;
-        Self::remove(left, left_data, right_data, left_hasher, right_hasher, len)
-            .map(|(_key, value)| value)
+        let (_, right_index, old_ideal, _) = left_data[old_index].data.take().unwrap();
+        let right_index = right_index.to_usize();
+        Self::mark_as_empty(old_ideal.to_usize(), old_index, left_data);
+
+        match Self::insert_one_sided(new, new_hash, left_data, right_data) {
+            Ok(left_index) => {
+                // the right key hasn't moved, so only the indexes between the two need updating
+                let &mut (_, ref mut paired_right_index, ..) =
+                    left_data[left_index].data.as_mut().unwrap();
+                *paired_right_index = I::from_usize(right_index);
+
+                let &mut (_, ref mut paired_left_index, ..) =
+                    right_data[right_index].data.as_mut().unwrap();
+                *paired_left_index = I::from_usize(left_index);
+            }
+            Err(new) => {
+                // there's no room for the new key, so take the right key out as well and insert
+                // the pair from scratch, which resizes the map to make room
+                let (right, _, right_ideal, right_hash) =
+                    right_data[right_index].data.take().unwrap();
+                Self::mark_as_empty(right_ideal.to_usize(), right_index, right_data);
+                *len -= 1;
+                self.insert_new(new, new_hash, right, right_hash);
+            }
+        }
+        true
     }
 
-    /// Removes a key from the right of the hashmap. Returns the value from the left of the hashmap
-    /// that was associated with this key, if it existed. Will remove both the left and right sides
-    /// of the pair, if it exists, meaning that `get_left` will no longer work for the value
-    /// associated with the key that is removed.
+    /// Replaces the right key paired with an existing left key, returning the right key it was
+    /// previously paired with. Just like `insert`, if `new_right` is already paired with another
+    /// left key, that pair is evicted from the map. Unlike `insert`, if `left` is not in the map
+    /// then nothing is inserted, and `None` is returned.
     ///
     /// ```
     /// # use isomorphism::BiMap;
     /// let mut map = BiMap::new();
     /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
     ///
-    /// assert_eq!(Some(&5), map.get_left("Hello"));
-    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// assert_eq!(Some(5), map.replace_right_of(&"Hello", 6));
+    /// assert_eq!(Some(&"Hello"), map.get_right(&6));
     ///
-    /// let old = map.remove_right(&5);
-    /// assert_eq!(Some("Hello"), old);
+    /// // ("World", 7) is evicted, because 7 is now paired with "Hello"
+    /// assert_eq!(Some(6), map.replace_right_of(&"Hello", 7));
+    /// assert_eq!(None, map.get_left("World"));
     ///
-    /// assert_eq!(None, map.get_left("Hello"));
-    /// assert_eq!(None, map.get_right(&5));
+    /// assert_eq!(None, map.replace_right_of(&"Goodbye", 8));
+    /// assert_eq!(1, map.len());
+    /// ```
+    pub fn replace_right_of(&mut self, left: &L, new_right: R) -> Option<R> {
+        let left_hash = Self::find_hash(left, &self.left_hasher);
+        let left_index = Self::find_index(left, left_hash, &self.left_data)?;
+        let right_hash = Self::find_hash(&new_right, &self.right_hasher);
+        Some(self.replace_right_at(left_index, left_hash, new_right, right_hash))
+    }
+
+    /// Lets `f` change the right key paired with an existing left key, in place. Since the right
+    /// key's hash may change, it is moved to wherever its new value belongs. Returns true if the
+    /// right key was changed, or false if the map was left unchanged because `left` is not in the
+    /// map or the changed right key is already paired with a different left key. `f` is given a
+    /// copy of the right key, so that the change can be rolled back in the second case.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// assert!(map.modify_right("Hello", |right| *right += 1));
+    /// assert_eq!(Some(&"Hello"), map.get_right(&6));
+    ///
+    /// // 7 is already paired with "World"
+    /// assert!(!map.modify_right("Hello", |right| *right += 1));
+    /// assert_eq!(Some(&6), map.get_left("Hello"));
+    /// assert!(!map.modify_right("Goodbye", |right| *right += 1));
     /// ```
-    pub fn remove_right<Q: ?Sized>(&mut self, right: &Q) -> Option<L>
+    pub fn modify_right<Q, F>(&mut self, left: &Q, f: F) -> bool
     where
-        R: Borrow<Q>,
-        Q: Hash + Eq,
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        R: Clone,
+        F: FnOnce(&mut R),
     {
+        let left_hash = Self::find_hash(&left, &self.left_hasher);
+        let left_index = match Self::find_index(left, left_hash, &self.left_data) {
+            Some(index) => index,
+            None => return false,
+        };
+        let right_index = self.left_data[left_index]
+            .data
+            .as_ref()
+            .unwrap()
+            .1
+            .to_usize();
+
+        let mut new_right = self.right_data[right_index]
+            .data
+            .as_ref()
+            .unwrap()
+            .0
+            .clone();
+        f(&mut new_right);
+        let right_hash = Self::find_hash(&new_right, &self.right_hasher);
+        match Self::find_index(&new_right, right_hash, &self.right_data) {
+            Some(index) if index != right_index => false,
+            _ => {
+                self.replace_right_at(left_index, left_hash, new_right, right_hash);
+                true
+            }
+        }
+    }
+
+    /// Replaces the right key paired with the left key at the given index, evicting any other pair
+    /// that the new right key belongs to, and returns the right key that was replaced.
+    fn replace_right_at(
+        &mut self,
+        left_index: usize,
+        left_hash: u64,
+        new_right: R,
+        right_hash: u64,
+    ) -> R {
         let &mut BiMap {
             ref mut len,
             ref mut left_data,
             ref mut right_data,
-            ref left_hasher,
-            ref right_hasher,
+            ..
         } = self;
-        Self::remove(right, right_data, left_data, right_hasher, left_hasher, len)
-            .map(|(_key, value)| value)
-    }
-}
+        let right_index = left_data[left_index].data.as_ref().unwrap().1.to_usize();
+        match Self::find_index(&new_right, right_hash, right_data) {
+            // the new right key is the one that is already there, so just swap it in
+            Some(index) if index == right_index => {
+                let (right, ..) = right_data[right_index].data.as_mut().unwrap();
+                return mem::replace(right, new_right);
+            }
+            // removing a pair never moves any other pair, so left_index is still valid
+            Some(index) => {
+                Self::remove_at(index, right_data, left_data, len);
+            }
+            None => (),
+        }
 
-impl<L, R, LH, RH, B> PartialEq for BiMap<L, R, LH, RH, B>
-where
-    L: Hash + Eq,
-    R: Hash + Eq,
-    LH: BuildHasher,
-    RH: BuildHasher,
-    B: BitField,
-{
-    fn eq(&self, other: &Self) -> bool {
-        self.len() == other.len()
-            && self.iter().all(|(left, right)| {
-                other.get_left(left).map_or(false, |r| *right == *r)
-                    && other.get_right(right).map_or(false, |l| *left == *l)
-            })
-    }
-}
+        let (old_right, _, old_ideal, _) = right_data[right_index].data.take().unwrap();
+        Self::mark_as_empty(old_ideal.to_usize(), right_index, right_data);
 
-impl<L, R, LH, RH, B> Eq for BiMap<L, R, LH, RH, B>
-where
-    L: Hash + Eq,
-    R: Hash + Eq,
-    LH: BuildHasher,
-    RH: BuildHasher,
-    B: BitField,
-{
-}
+        match Self::insert_one_sided(new_right, right_hash, right_data, left_data) {
+            Ok(right_index) => {
+                // the left key hasn't moved, so only the indexes between the two need updating
+                let &mut (_, ref mut paired_right_index, ..) =
+                    left_data[left_index].data.as_mut().unwrap();
+                *paired_right_index = I::from_usize(right_index);
 
-impl<L, R, LH, RH, B> Debug for BiMap<L, R, LH, RH, B>
-where
-    L: Debug,
-    R: Debug,
-{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_map().entries(self.iter()).finish()
+                let &mut (_, ref mut paired_left_index, ..) =
+                    right_data[right_index].data.as_mut().unwrap();
+                *paired_left_index = I::from_usize(left_index);
+            }
+            Err(new_right) => {
+                // there's no room for the new key, so take the left key out as well and insert
+                // the pair from scratch, which resizes the map to make room
+                let (left, _, left_ideal, _) = left_data[left_index].data.take().unwrap();
+                Self::mark_as_empty(left_ideal.to_usize(), left_index, left_data);
+                *len -= 1;
+                self.insert_new(left, left_hash, new_right, right_hash);
+            }
+        }
+        old_right
     }
-}
-
-impl<'a, L, R, LH, RH, B> IntoIterator for &'a BiMap<L, R, LH, RH, B> {
-    type Item = (&'a L, &'a R);
-    type IntoIter = Iter<'a, L, R, B>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        let &BiMap {
-            ref left_data,
-            ref right_data,
+    /// Replaces the right key paired with the left key at the given index, returning the right key
+    /// that was replaced. Unlike `replace_right_at`, this never moves a left key: if the new right
+    /// key is already paired with another left key, or can't be placed without resizing the map,
+    /// the map is left unchanged and the new right key is handed back.
+    fn try_replace_right_at(&mut self, left_index: usize, new_right: R) -> Result<R, R> {
+        let right_hash = Self::find_hash(&new_right, &self.right_hasher);
+        let &mut BiMap {
+            ref mut left_data,
+            ref mut right_data,
             ..
         } = self;
-        Iter::new(left_data.iter(), right_data)
+        Self::try_replace_paired(left_index, new_right, right_hash, left_data, right_data)
     }
-}
 
-impl<L, R, LH, RH, B> IntoIterator for BiMap<L, R, LH, RH, B> {
-    type Item = (L, R);
-    type IntoIter = IntoIter<L, R, B>;
+    /// Replaces the value paired with the key at the given index in the key_data section of the
+    /// hashmap, returning the value that was replaced. This never moves the key: if the new value
+    /// is already paired with another key, or can't be placed without resizing the map, the map is
+    /// left unchanged and the new value is handed back.
+    fn try_replace_paired<K, V>(
+        key_index: usize,
+        new_value: V,
+        value_hash: u64,
+        key_data: &mut [Bucket<K, I, B>],
+        value_data: &mut [Bucket<V, I, B>],
+    ) -> Result<V, V>
+    where
+        V: Eq,
+    {
+        let value_index = key_data[key_index].data.as_ref().unwrap().1.to_usize();
+        match Self::find_index(&new_value, value_hash, value_data) {
+            Some(index) if index == value_index => {
+                let (value, ..) = value_data[value_index].data.as_mut().unwrap();
+                return Ok(mem::replace(value, new_value));
+            }
+            Some(_) => return Err(new_value),
+            None => (),
+        }
 
-    fn into_iter(self) -> Self::IntoIter {
-        let BiMap {
-            left_data,
-            right_data,
-            ..
-        } = self;
-        IntoIter::new(left_data, right_data)
+        let (old_value, _, old_ideal, old_hash) = value_data[value_index].data.take().unwrap();
+        Self::mark_as_empty(old_ideal.to_usize(), value_index, value_data);
+
+        let (result, value_index) =
+            match Self::insert_one_sided(new_value, value_hash, value_data, key_data) {
+                Ok(value_index) => (Ok(old_value), value_index),
+                Err(new_value) => {
+                    // a failed insert leaves everything where it was, so the old value's bucket is
+                    // still free and always within its neighbourhood
+                    match Self::insert_one_sided(old_value, old_hash, value_data, key_data) {
+                        Ok(value_index) => (Err(new_value), value_index),
+                        Err(_) => unreachable!("the old value always fits back in"),
+                    }
+                }
+            };
+
+        let &mut (_, ref mut paired_value_index, ..) = key_data[key_index].data.as_mut().unwrap();
+        *paired_value_index = I::from_usize(value_index);
+
+        let &mut (_, ref mut paired_key_index, ..) = value_data[value_index].data.as_mut().unwrap();
+        *paired_key_index = I::from_usize(key_index);
+        result
     }
-}
 
-impl<L, R, LH, RH, B> FromIterator<(L, R)> for BiMap<L, R, LH, RH, B>
-where
-    L: Hash + Eq,
-    R: Hash + Eq,
-    LH: BuildHasher + Default,
-    RH: BuildHasher + Default,
-    B: BitField,
-{
-    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
-        let mut output = BiMapBuilder::new()
-            .left_hasher(Default::default())
-            .right_hasher(Default::default())
-            .bitfield::<B>()
-            .finish();
-        output.extend(iter);
-        output
+    /// Returns true if the map is bounded, and holds as many pairs as it is allowed to.
+    fn at_max_len(&self) -> bool {
+        matches!(self.max_len, Some(max_len) if self.len >= max_len)
     }
-}
 
-impl<L, R, LH, RH, B> Extend<(L, R)> for BiMap<L, R, LH, RH, B>
-where
-    L: Hash + Eq,
-    R: Hash + Eq,
-    LH: BuildHasher,
-    RH: BuildHasher,
-    B: BitField,
-{
-    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
-        for (left, right) in iter {
-            self.insert(left, right);
+    /// Inserts a pair whose keys do not already exist within the map, given the hashes of both
+    /// keys. Returns the index that the left key was inserted at. Panics if either key's
+    /// neighbourhood is already full of keys with the same hash, or if the map would need more
+    /// buckets than its pair index type can count.
+    fn insert_new(&mut self, left: L, left_hash: u64, right: R, right_hash: u64) -> usize {
+        match self.try_insert_new(left, left_hash, right, right_hash) {
+            Ok(left_index) => left_index,
+            Err(_) if self.is_either_saturated(left_hash, right_hash) => {
+                panic!("too many keys with the same hash to fit in one neighbourhood")
+            }
+            Err(_) => panic!("capacity overflow"),
         }
     }
-}
-
-#[cfg(feature = "serde")]
-impl<L, R, LH, RH, B> Serialize for BiMap<L, R, LH, RH, B>
-where
-    L: Serialize,
-    R: Serialize,
-{
-    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        use serde::ser::SerializeSeq;
 
-        let mut seq = serializer.serialize_seq(Some(self.len))?;
-        for (ref left, ref right) in self.iter() {
-            seq.serialize_element(&(left, right))?;
+    /// Returns the number of buckets the map should grow to next, or `None` if it already has as
+    /// many as its pair index type can count.
+    fn grown_capacity(&self) -> Option<usize> {
+        let capacity = match self.left_data.len() {
+            0 => min_buckets::<B>(),
+            len => len
+                .checked_mul(self.growth_factor)
+                .and_then(usize::checked_next_power_of_two)
+                .unwrap_or(usize::MAX),
+        };
+        let capacity = cmp::min(capacity, pair_index::max_buckets::<I>());
+        if capacity > self.left_data.len() {
+            Some(capacity)
+        } else {
+            None
         }
-
-        seq.end()
     }
-}
-
-#[cfg(feature = "serde")]
-impl<'de, L, R, LH, RH, B> Deserialize<'de> for BiMap<L, R, LH, RH, B>
-where
-    L: Hash + Eq + Deserialize<'de>,
-    R: Hash + Eq + Deserialize<'de>,
-    LH: BuildHasher + Default,
-    RH: BuildHasher + Default,
-    B: BitField,
-{
-    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        use std::fmt;
-        use std::marker::PhantomData;
 
-        use serde::de::{MapAccess, Visitor};
+    /// The same as `insert_new`, but if the pair doesn't fit and growing the map can't help -
+    /// because it already has as many buckets as its pair index type can count, or because either
+    /// key's neighbourhood is full of keys with the same hash - the map is left unchanged and the
+    /// pair is handed back. A map that can't grow any more ignores its load factor, and keeps
+    /// filling its buckets until a key can't be placed at all.
+    fn try_insert_new(
+        &mut self,
+        left: L,
+        left_hash: u64,
+        right: R,
+        right_hash: u64,
+    ) -> Result<usize, (L, R)> {
+        let can_grow = self.grown_capacity().is_some();
 
-        struct MapVisitor<L, R, LH, RH, B> {
-            marker: PhantomData<BiMap<L, R, LH, RH, B>>,
-        }
+        // attempt to insert, hold onto the keys if it fails
+        let result: Result<usize, (L, R)> =
+            if can_grow && self.len as f32 >= self.left_data.len() as f32 * self.load_factor {
+                Err((left, right))
+            } else {
+                let &mut BiMap {
+                    ref mut left_data,
+                    ref mut right_data,
+                    ..
+                } = self;
+                match Self::insert_one_sided(left, left_hash, left_data, right_data) {
+                    Ok(left_index) => {
+                        match Self::insert_one_sided(right, right_hash, right_data, left_data) {
+                            Ok(right_index) => {
+                                let &mut (_, ref mut paired_right_index, ..) =
+                                    left_data[left_index].data.as_mut().unwrap();
+                                *paired_right_index = I::from_usize(right_index);
 
-        impl<'de, L, R, LH, RH, B> Visitor<'de> for MapVisitor<L, R, LH, RH, B>
-        where
-            L: Hash + Eq + Deserialize<'de>,
-            R: Hash + Eq + Deserialize<'de>,
-            LH: BuildHasher + Default,
-            RH: BuildHasher + Default,
-            B: BitField,
-        {
-            type Value = BiMap<L, R, LH, RH, B>;
+                                let &mut (_, ref mut paired_left_index, ..) =
+                                    right_data[right_index].data.as_mut().unwrap();
+                                *paired_left_index = I::from_usize(left_index);
+                                Ok(left_index)
+                            }
+                            Err(right) => {
+                                let (left, _, left_ideal, _) =
+                                    left_data[left_index].data.take().unwrap();
+                                Self::mark_as_empty(left_ideal.to_usize(), left_index, left_data);
+                                Err((left, right))
+                            }
+                        }
+                    }
+                    Err(left) => Err((left, right)),
+                }
+            };
 
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a map")
+        match result {
+            Ok(left_index) => {
+                self.len += 1;
+                Ok(left_index)
             }
-
-            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
-                let builder = BiMapBuilder::new()
-                    .left_hasher(Default::default())
-                    .right_hasher(Default::default())
-                    .bitfield::<B>();
-                let mut output = if let Some(size) = map.size_hint() {
-                    builder.capacity(size).finish()
-                } else {
-                    builder.finish()
+            Err((left, right)) => {
+                let capacity = match self.grown_capacity() {
+                    Some(capacity) => capacity,
+                    None => return Err((left, right)),
                 };
 
-                while let Some((left, right)) = map.next_entry()? {
-                    output.insert(left, right);
+                // keys with the same hash always share an ideal position, however big the map
+                // gets, so once a whole neighbourhood is taken by them resizing can't help
+                if self.is_either_saturated(left_hash, right_hash) {
+                    return Err((left, right));
                 }
 
-                Ok(output)
+                // resize, as we were unable to insert
+                self.resize(capacity);
+                self.try_insert_new(left, left_hash, right, right_hash)
             }
         }
+    }
 
-        let visitor = MapVisitor {
-            marker: PhantomData,
-        };
-        deserializer.deserialize_map(visitor)
+    /// Returns true if the neighbourhood of the left hash or of the right hash is saturated.
+    fn is_either_saturated(&self, left_hash: u64, right_hash: u64) -> bool {
+        Self::is_saturated(left_hash, &self.left_data)
+            || Self::is_saturated(right_hash, &self.right_data)
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::BiMap;
+    /// Returns true if every bucket in the neighbourhood that a key with the given hash belongs in
+    /// is taken by a key with exactly the same hash.
+    fn is_saturated<K>(hash: u64, key_data: &[Bucket<K, I, B>]) -> bool {
+        let len = key_data.len();
+        if len == 0 {
+            return false;
+        }
+
+        let ideal = Self::ideal_index(hash, len);
+        let same_hash = key_data[ideal]
+            .neighbourhood
+            .iter()
+            .filter_map(|offset| key_data[(ideal + offset) & (len - 1)].data.as_ref())
+            .filter(|&&(.., key_hash)| key_hash == hash)
+            .count();
+        same_hash >= B::size()
+    }
+
+    /// Replaces the backing arrays of the hashmap with new arrays containing `capacity` buckets,
+    /// and re-inserts every pair into them.
+    fn resize(&mut self, capacity: usize) {
+        self.resize_into(Bucket::empty_vec(capacity), Bucket::empty_vec(capacity));
+    }
+
+    /// Replaces the backing arrays of the hashmap with the given empty arrays, and re-inserts
+    /// every pair into them.
+    fn resize_into(
+        &mut self,
+        left_data: Box<[Bucket<L, I, B>]>,
+        right_data: Box<[Bucket<R, I, B>]>,
+    ) {
+        self.len = 0;
+        let old_left_data = mem::replace(&mut self.left_data, left_data);
+        let mut old_right_data = mem::replace(&mut self.right_data, right_data);
+
+        // the hashers have not changed, so the stored hashes can be reused
+        for bucket in old_left_data.into_vec() {
+            if let Some((left, right_index, _, left_hash)) = bucket.data {
+                let (right, _, _, right_hash) =
+                    old_right_data[right_index.to_usize()].data.take().unwrap();
+                self.insert_new(left, left_hash, right, right_hash);
+            }
+        }
+    }
+
+    /// Reserves space for at least `additional` more pairs to be inserted without the map needing
+    /// to be resized. Does nothing if the map already has enough space. A map never reserves more
+    /// buckets than its pair index type can count; it fills the ones it has instead.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// map.reserve(1000);
+    /// assert!(map.capacity() >= 1001);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let required = buckets_for::<B>(self.len + additional, self.load_factor);
+        let required = cmp::min(required, pair_index::max_buckets::<I>());
+        if required > self.left_data.len() {
+            self.resize(required);
+        }
+    }
+
+    /// Tries to reserve space for at least `additional` more pairs to be inserted without the map
+    /// needing to be resized, in the same way as `reserve`. Rather than aborting if the memory
+    /// can't be allocated, this returns an error and leaves the map unchanged.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, TryReserveError};
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(Ok(()), map.try_reserve(1000));
+    /// assert!(map.capacity() >= 1001);
+    ///
+    /// assert_eq!(Err(TryReserveError::CapacityOverflow), map.try_reserve(usize::MAX));
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .and_then(|pairs| checked_buckets_for::<B>(pairs, self.load_factor))
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required > self.left_data.len() {
+            let left_data = Bucket::try_empty_vec(required)?;
+            let right_data = Bucket::try_empty_vec(required)?;
+            self.resize_into(left_data, right_data);
+        }
+        Ok(())
+    }
+
+    /// Shrinks the capacity of the map as much as possible, while still leaving enough space for
+    /// the pairs that are currently inside it. Does nothing if the map is already as small as it
+    /// can be.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::with_capacity(1000);
+    /// map.insert("Hello", 5);
+    ///
+    /// map.shrink_to_fit();
+    /// assert!(map.capacity() < 1000);
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let required = buckets_for::<B>(self.len, self.load_factor);
+        if required < self.left_data.len() {
+            self.resize(required);
+        }
+    }
+
+    /// Shrinks the capacity of the map, while still leaving enough space for the pairs that are
+    /// currently inside it, and for at least `min_capacity` pairs in total. This can be used to
+    /// free most of the memory used by a map that is expected to grow again. Does nothing if the
+    /// map is already that small.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::with_capacity(1000);
+    /// map.insert("Hello", 5);
+    ///
+    /// map.shrink_to(100);
+    /// assert!(map.capacity() < 1000);
+    /// assert!(map.capacity() >= 100);
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let required = buckets_for::<B>(cmp::max(self.len, min_capacity), self.load_factor);
+        let required = cmp::min(required, pair_index::max_buckets::<I>());
+        if required < self.left_data.len() {
+            self.resize(required);
+        }
+    }
+
+    /// Re-inserts every pair into new backing arrays of the same capacity, moving each key as close
+    /// to its ideal position as it can get. After a long run of inserts and removes, keys can be
+    /// left displaced far from where they would be placed in a fresh map, making lookups slower.
+    /// This does not change the contents of the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u32, u32> = (0..100).map(|x| (x, x)).collect();
+    /// map.retain(|&left, _| left % 3 == 0);
+    /// let before = map.clone();
+    ///
+    /// map.defragment();
+    /// assert_eq!(before, map);
+    /// ```
+    pub fn defragment(&mut self) {
+        let capacity = self.left_data.len();
+        self.resize(capacity);
+    }
+
+    /// Looks up a key in the key_data section of the hashap, and if it exists returns it from the
+    /// value_data section of the hashap. Returns the stored key and the value that is associated
+    /// with it, if they exist.
+    fn get<'a, Q, K, V, KH>(
+        key: &Q,
+        key_data: &'a [Bucket<K, I, B>],
+        value_data: &'a [Bucket<V, I, B>],
+        key_hasher: &KH,
+    ) -> Option<(&'a K, &'a V)>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Hash + Eq + Borrow<Q>,
+        KH: BuildHasher,
+    {
+        let len = key_data.len();
+        if len == 0 {
+            return None;
+        }
+        let ideal = Self::find_ideal_index(&key, key_hasher, len);
+
+        let neighbourhood = key_data[ideal].neighbourhood;
+        neighbourhood
+            .iter()
+            .filter_map(|offset| key_data[(ideal + offset) & (len - 1)].data.as_ref())
+            .filter(|(candidate_key, ..)| candidate_key.borrow() == key)
+            .filter_map(|(key, pair_index, ..)| {
+                value_data[pair_index.to_usize()]
+                    .data
+                    .as_ref()
+                    .map(|(value, ..)| (key, value))
+            })
+            .next()
+    }
+
+    /// Looks up a key in the key_data section of the hashmap, and returns whether or not it
+    /// exists. Unlike `get`, this never touches the value_data section of the hashmap.
+    fn contains<Q, K, KH>(key: &Q, key_data: &[Bucket<K, I, B>], key_hasher: &KH) -> bool
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Hash + Eq + Borrow<Q>,
+        KH: BuildHasher,
+    {
+        let len = key_data.len();
+        if len == 0 {
+            return false;
+        }
+        let ideal = Self::find_ideal_index(&key, key_hasher, len);
+
+        let neighbourhood = key_data[ideal].neighbourhood;
+        neighbourhood
+            .iter()
+            .filter_map(|offset| key_data[(ideal + offset) & (len - 1)].data.as_ref())
+            .any(|(candidate_key, ..)| candidate_key.borrow() == key)
+    }
+
+    /// Finds the index at which a key is stored in the key_data section of the hashmap, given the
+    /// hash of that key. Returns None if the key does not exist.
+    fn find_index<Q, K>(key: &Q, hash: u64, key_data: &[Bucket<K, I, B>]) -> Option<usize>
+    where
+        Q: ?Sized + Eq,
+        K: Borrow<Q>,
+    {
+        let len = key_data.len();
+        if len == 0 {
+            return None;
+        }
+
+        let ideal = Self::ideal_index(hash, len);
+        key_data[ideal]
+            .neighbourhood
+            .iter()
+            .map(|offset| (ideal + offset) & (len - 1))
+            .find(|&index| match key_data[index].data {
+                Some((ref candidate_key, ..)) => candidate_key.borrow() == key,
+                None => false,
+            })
+    }
+
+    /// Removes a key from the key_data section of the hashmap, and removes the value from the
+    /// value_data section of the hashmap. Returns the value that is associated with the key, if it
+    /// exists.
+    fn remove<Q, K, V, KH>(
+        key: &Q,
+        key_data: &mut [Bucket<K, I, B>],
+        value_data: &mut [Bucket<V, I, B>],
+        key_hasher: &KH,
+        map_len: &mut usize,
+    ) -> Option<(K, V)>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Hash + Eq + Borrow<Q>,
+        KH: BuildHasher,
+    {
+        if key_data.is_empty() {
+            return None;
+        }
+        let hash = Self::find_hash(&key, key_hasher);
+        Self::remove_hashed(key, hash, key_data, value_data, map_len)
+    }
+
+    /// The same as `remove`, but for a key whose hash has already been found, so that it doesn't
+    /// need to be hashed again.
+    fn remove_hashed<Q, K, V>(
+        key: &Q,
+        hash: u64,
+        key_data: &mut [Bucket<K, I, B>],
+        value_data: &mut [Bucket<V, I, B>],
+        map_len: &mut usize,
+    ) -> Option<(K, V)>
+    where
+        Q: ?Sized + Eq,
+        K: Borrow<Q>,
+    {
+        Self::find_index(key, hash, key_data)
+            .map(|index| Self::remove_at(index, key_data, value_data, map_len))
+    }
+
+    /// Removes the key stored at a given index in the key_data section of the hashmap, and removes
+    /// its matching value from the value_data section of the hashmap. The bucket at the given
+    /// index must be full.
+    fn remove_at<K, V>(
+        index: usize,
+        key_data: &mut [Bucket<K, I, B>],
+        value_data: &mut [Bucket<V, I, B>],
+        map_len: &mut usize,
+    ) -> (K, V) {
+        let (key, value_index, ideal_index, _) = key_data[index].data.take().unwrap();
+        let value_index = value_index.to_usize();
+        Self::mark_as_empty(ideal_index.to_usize(), index, key_data);
+
+        let (value, _, ideal_value_index, _) = value_data[value_index].data.take().unwrap();
+        Self::mark_as_empty(ideal_value_index.to_usize(), value_index, value_data);
+
+        *map_len -= 1;
+
+        (key, value)
+    }
+
+    /// Gets a key from the left of the hashmap. Returns the value from the right of the hashmap
+    /// that associates with this key, if it exists.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(None, map.get_left("Hello"));
+    ///
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn get_left<'a, Q>(&'a self, left: &Q) -> Option<&'a R>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let BiMap {
+            left_data,
+            right_data,
+            left_hasher,
+            ..
+        } = self;
+        Self::get(left, left_data, right_data, left_hasher).map(|(_key, value)| value)
+    }
+
+    /// Looks up several left keys at once, in the same way as `get_left`. The results are returned
+    /// in the same order as the keys, and no intermediate collection is allocated.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// assert_eq!(
+    ///     [Some(&7), None, Some(&5)],
+    ///     map.get_many_left(["World", "Goodbye", "Hello"])
+    /// );
+    /// ```
+    pub fn get_many_left<'a, Q, const N: usize>(&'a self, keys: [&Q; N]) -> [Option<&'a R>; N]
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        keys.map(|key| self.get_left(key))
+    }
+
+    /// Gets a key from the right of the hashmap. Returns the value from the left of the hashmap
+    /// that associates with this key, if it exists.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(None, map.get_right(&5));
+    ///
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// ```
+    pub fn get_right<'a, Q>(&'a self, right: &Q) -> Option<&'a L>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let BiMap {
+            right_data,
+            left_data,
+            right_hasher,
+            ..
+        } = self;
+        Self::get(right, right_data, left_data, right_hasher).map(|(_key, value)| value)
+    }
+
+    /// Gets a key from the left of the hashmap, in the same way as `get_left`, but returns an
+    /// error rather than `None` if the key isn't in the map.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, KeyNotFound};
+    /// fn double(map: &BiMap<&str, u32>, key: &str) -> Result<u32, KeyNotFound> {
+    ///     Ok(map.try_get_left(key)? * 2)
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Ok(10), double(&map, "Hello"));
+    /// assert_eq!(Err(KeyNotFound), double(&map, "World"));
+    /// ```
+    pub fn try_get_left<'a, Q>(&'a self, left: &Q) -> Result<&'a R, KeyNotFound>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_left(left).ok_or(KeyNotFound)
+    }
+
+    /// Gets a key from the right of the hashmap, in the same way as `get_right`, but returns an
+    /// error rather than `None` if the key isn't in the map.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, KeyNotFound};
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Ok(&"Hello"), map.try_get_right(&5));
+    /// assert_eq!(Err(KeyNotFound), map.try_get_right(&7));
+    /// ```
+    pub fn try_get_right<'a, Q>(&'a self, right: &Q) -> Result<&'a L, KeyNotFound>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_right(right).ok_or(KeyNotFound)
+    }
+
+    /// Gets a key from the left of the hashmap. Returns the key as it is stored in the map, along
+    /// with the value from the right of the hashmap that associates with it, if they exist. This
+    /// is useful when the stored key carries more than the key used to look it up, such as an
+    /// interned `String` found with a `&str`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello".to_owned(), 5);
+    ///
+    /// let probe: &str = "Hello";
+    /// let (canonical, value) = map.get_left_key_value(probe).unwrap();
+    /// assert_eq!("Hello", canonical);
+    /// assert_eq!(&5, value);
+    ///
+    /// // the returned key is the String that lives inside the map
+    /// let interned: &String = canonical;
+    /// assert!(std::ptr::eq(interned, map.get_right(&5).unwrap()));
+    /// ```
+    pub fn get_left_key_value<'a, Q>(&'a self, left: &Q) -> Option<(&'a L, &'a R)>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let BiMap {
+            left_data,
+            right_data,
+            left_hasher,
+            ..
+        } = self;
+        Self::get(left, left_data, right_data, left_hasher)
+    }
+
+    /// Gets a key from the right of the hashmap. Returns the key as it is stored in the map, along
+    /// with the value from the left of the hashmap that associates with it, if they exist.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert(5, "Hello".to_owned());
+    ///
+    /// assert_eq!(Some((&"Hello".to_owned(), &5)), map.get_right_key_value("Hello"));
+    /// assert_eq!(None, map.get_right_key_value("World"));
+    /// ```
+    pub fn get_right_key_value<'a, Q>(&'a self, right: &Q) -> Option<(&'a R, &'a L)>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let BiMap {
+            right_data,
+            left_data,
+            right_hasher,
+            ..
+        } = self;
+        Self::get(right, right_data, left_data, right_hasher)
+    }
+
+    /// Looks up a key from the left of the map, and returns a handle to its pair that can be
+    /// passed to `resolve` to get the pair back again cheaply. The handle is only valid until the
+    /// map is next modified: any insert or removal may move pairs to different buckets, after which
+    /// resolving an old handle panics or returns an unrelated pair.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let handle = map.left_handle("Hello").unwrap();
+    /// assert_eq!((&"Hello", &5), map.resolve(handle));
+    /// assert_eq!(None, map.left_handle("World"));
+    /// ```
+    pub fn left_handle<Q>(&self, left: &Q) -> Option<Handle>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = Self::find_hash(&left, &self.left_hasher);
+        Self::find_index(left, hash, &self.left_data).map(Handle)
+    }
+
+    /// Looks up a key from the right of the map, and returns a handle to its pair. The same
+    /// caveats apply as for `left_handle`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let handle = map.right_handle(&5).unwrap();
+    /// assert_eq!(Some(handle), map.left_handle("Hello"));
+    /// ```
+    pub fn right_handle<Q>(&self, right: &Q) -> Option<Handle>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = Self::find_hash(&right, &self.right_hasher);
+        Self::find_index(right, hash, &self.right_data)
+            .and_then(|index| self.right_data[index].data.as_ref())
+            .map(|&(_, left_index, ..)| Handle(left_index.to_usize()))
+    }
+
+    /// Returns the pair that a handle from `left_handle` or `right_handle` refers to, in constant
+    /// time and without hashing anything.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let handle = map.left_handle("Hello").unwrap();
+    /// for _ in 0..3 {
+    ///     assert_eq!(&5, map.resolve(handle).1);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Handles are invalidated by any change to the map. Resolving a handle after the map has been
+    /// modified may panic, or may return a different pair than the one it was created for.
+    pub fn resolve(&self, handle: Handle) -> (&L, &R) {
+        let (left, right_index, ..) = self
+            .left_data
+            .get(handle.0)
+            .and_then(|bucket| bucket.data.as_ref())
+            .expect("handle does not refer to a pair in this bimap");
+        let (right, ..) = self.right_data[right_index.to_usize()]
+            .data
+            .as_ref()
+            .unwrap();
+        (left, right)
+    }
+
+    /// Returns true if the map contains a pair with the given left key. This is cheaper than
+    /// `get_left(left).is_some()`, as it only looks at the left side of the map - finding the right
+    /// key would mean a second lookup into the right buckets, which are often not in cache.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert!(!map.contains_left("Hello"));
+    ///
+    /// map.insert("Hello", 5);
+    /// assert!(map.contains_left("Hello"));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let BiMap {
+            left_data,
+            left_hasher,
+            ..
+        } = self;
+        Self::contains(left, left_data, left_hasher)
+    }
+
+    /// Returns true if the map contains a pair with the given right key. Like `contains_left`, this
+    /// never looks at the other side of the map, so it is cheaper than `get_right(right).is_some()`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert!(!map.contains_right(&5));
+    ///
+    /// map.insert("Hello", 5);
+    /// assert!(map.contains_right(&5));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let BiMap {
+            right_data,
+            right_hasher,
+            ..
+        } = self;
+        Self::contains(right, right_data, right_hasher)
+    }
+
+    /// Removes a key from the left of the hashmap. Returns the value from the right of the hashmap
+    /// that was associated with this key, if it existed. Will remove both the left and right sides
+    /// of the pair, if it exists, meaning that `get_right` will no longer work for the value
+    /// associated with the key that is removed.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    ///
+    /// let old = map.remove_left("Hello");
+    /// assert_eq!(Some(5), old);
+    ///
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn remove_left<Q>(&mut self, left: &Q) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref left_hasher,
+            ..
+        } = self;
+        Self::remove(left, left_data, right_data, left_hasher, len).map(|(_key, value)| value)
+    }
+
+    /// Removes a key from the right of the hashmap. Returns the value from the left of the hashmap
+    /// that was associated with this key, if it existed. Will remove both the left and right sides
+    /// of the pair, if it exists, meaning that `get_left` will no longer work for the value
+    /// associated with the key that is removed.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    ///
+    /// let old = map.remove_right(&5);
+    /// assert_eq!(Some("Hello"), old);
+    ///
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn remove_right<Q>(&mut self, right: &Q) -> Option<L>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref right_hasher,
+            ..
+        } = self;
+        Self::remove(right, right_data, left_data, right_hasher, len).map(|(_key, value)| value)
+    }
+
+    /// Removes a key from the left of the hashmap, returning both the stored left key and the
+    /// right value it was paired with, if they existed. This is the same as `remove_left`, except
+    /// that ownership of the left key is handed back as well.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello".to_owned(), 5);
+    ///
+    /// assert_eq!(Some(("Hello".to_owned(), 5)), map.remove_left_pair("Hello"));
+    /// assert_eq!(None, map.get_right(&5));
+    /// assert_eq!(None, map.remove_left_pair("Hello"));
+    /// ```
+    pub fn remove_left_pair<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref left_hasher,
+            ..
+        } = self;
+        Self::remove(left, left_data, right_data, left_hasher, len)
+    }
+
+    /// Removes a key from the right of the hashmap, returning both the left value it was paired
+    /// with and the stored right key, if they existed. This is the same as `remove_right`, except
+    /// that ownership of the right key is handed back as well.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert(5, "Hello".to_owned());
+    ///
+    /// assert_eq!(Some((5, "Hello".to_owned())), map.remove_right_pair("Hello"));
+    /// assert_eq!(None, map.get_left(&5));
+    /// assert_eq!(None, map.remove_right_pair("Hello"));
+    /// ```
+    pub fn remove_right_pair<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref right_hasher,
+            ..
+        } = self;
+        Self::remove(right, right_data, left_data, right_hasher, len)
+            .map(|(right, left)| (left, right))
+    }
+
+    /// Removes the pair made up of `left` and `right`, but only if `left` is currently paired with
+    /// exactly `right`. Returns true if the pair was removed. If either key is paired with something
+    /// else, the map is left unchanged.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert!(!map.remove_pair("Hello", &7));
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    ///
+    /// assert!(map.remove_pair("Hello", &5));
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove_pair<Q, S>(&mut self, left: &Q, right: &S) -> bool
+    where
+        L: Borrow<Q>,
+        R: Borrow<S>,
+        Q: ?Sized + Hash + Eq,
+        S: ?Sized + Eq,
+    {
+        let hash = Self::find_hash(&left, &self.left_hasher);
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ..
+        } = self;
+        match Self::find_index(left, hash, left_data) {
+            Some(index) => {
+                let right_index = left_data[index].data.as_ref().unwrap().1.to_usize();
+                let (paired_right, ..) = right_data[right_index].data.as_ref().unwrap();
+                if paired_right.borrow() == right {
+                    Self::remove_at(index, left_data, right_data, len);
+                    true
+                } else {
+                    false
+                }
+            }
+            None => false,
+        }
+    }
+
+    /// Removes and returns an arbitrary pair from the map, or `None` if the map is empty. Which
+    /// pair is removed is unspecified.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(Some(("Hello", 5)), map.pop());
+    /// assert_eq!(None, map.pop());
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn pop(&mut self) -> Option<(L, R)> {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref mut pop_hint,
+            ..
+        } = self;
+        if *len == 0 {
+            return None;
+        }
+
+        // carry on from where the last pop left off, so that popping every pair in turn doesn't
+        // keep scanning over the buckets it has already emptied. The hint may be stale, so wrap
+        // around to make sure every bucket is still checked
+        let buckets = left_data.len();
+        let start = *pop_hint & (buckets - 1);
+        let index = (start..buckets)
+            .chain(0..start)
+            .find(|&index| left_data[index].data.is_some())
+            .unwrap();
+        *pop_hint = index;
+        Some(Self::remove_at(index, left_data, right_data, len))
+    }
+
+    /// Removes every pair from the map. This does not free the memory used by the map, so its
+    /// capacity is unchanged and it can be refilled without being resized.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// let capacity = map.capacity();
+    ///
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(capacity, map.capacity());
+    /// ```
+    pub fn clear(&mut self) {
+        let empty = B::empty();
+        for bucket in self.left_data.iter_mut() {
+            bucket.data = None;
+            bucket.neighbourhood = empty;
+        }
+        for bucket in self.right_data.iter_mut() {
+            bucket.data = None;
+            bucket.neighbourhood = empty;
+        }
+        self.len = 0;
+    }
+
+    /// Clears the map, returning all of its pairs as an iterator. The capacity of the map is kept
+    /// so that it can be refilled without being resized. If the iterator is dropped before it has
+    /// been fully consumed, the remaining pairs are dropped along with it. If it is leaked instead,
+    /// the pairs that were not yet yielded are left in the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// let mut pairs: Vec<_> = map.drain().collect();
+    /// pairs.sort();
+    ///
+    /// assert_eq!(vec![("Hello", 5), ("World", 7)], pairs);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, L, R, B, I> {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ..
+        } = self;
+        Drain::new(left_data, right_data, len)
+    }
+
+    /// Moves every pair out of `other` and into this map, leaving `other` empty but with its
+    /// capacity unchanged. Each pair is inserted as if by `insert`, so pairs from `other` win any
+    /// conflicts, evicting the pairs in this map that share their left or right key. Like
+    /// `insert`, every pair is moved across, even past the `max_len` this map was built with.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// let mut other = BiMap::new();
+    /// other.insert("Goodbye", 5);
+    ///
+    /// map.append(&mut other);
+    /// assert!(other.is_empty());
+    /// assert_eq!(2, map.len());
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(Some(&"Goodbye"), map.get_right(&5));
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        self.reserve(other.len);
+        for (left, right) in other.drain() {
+            self.insert(left, right);
+        }
+    }
+
+    /// Inserts every pair from the iterator, as if by `insert`, and returns how many of those
+    /// inserts evicted a pair that was already in the map. This is the same as `extend`, but makes
+    /// it possible to tell whether any pairs were silently replaced by later ones. Like `insert`,
+    /// every pair is inserted, even past the `max_len` the map was built with.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    ///
+    /// // ("World", 5) evicts ("Hello", 5), and ("World", 7) evicts ("World", 5)
+    /// let evicted = map.insert_all(vec![("Hello", 5), ("World", 5), ("World", 7)]);
+    /// assert_eq!(2, evicted);
+    /// assert_eq!(1, map.len());
+    /// ```
+    pub fn insert_all<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) -> usize {
+        iter.into_iter()
+            .map(|(left, right)| self.insert(left, right))
+            .filter(|evicted| !matches!(evicted, (None, None)))
+            .count()
+    }
+
+    /// Moves every pair out of `other` and into this map, using `resolve` to decide what happens
+    /// when an incoming pair conflicts with a pair already in this map. Pairs that don't conflict
+    /// with anything are inserted directly.
+    ///
+    /// `resolve` is called with the existing pair followed by the incoming pair. If the incoming
+    /// pair conflicts with two different existing pairs - one sharing its left key, the other its
+    /// right key - then `resolve` is called for the left conflict first, and then for the right
+    /// one, whatever the first call returned. The incoming pair is only inserted if every call
+    /// returns `Keep::KeepOther`, so an existing pair that was chosen to be kept is never evicted.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, Keep};
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// let mut other = BiMap::new();
+    /// other.insert("Hello", 6);
+    /// other.insert("Goodbye", 7);
+    ///
+    /// // keep whichever pair has the larger right value
+    /// map.merge_with(other, |_, old, _, new| if new > old { Keep::KeepOther } else { Keep::KeepSelf });
+    /// assert_eq!(Some(&6), map.get_left("Hello"));
+    /// assert_eq!(Some(&7), map.get_left("World"));
+    /// assert_eq!(None, map.get_left("Goodbye"));
+    /// ```
+    pub fn merge_with<F>(&mut self, other: Self, mut resolve: F)
+    where
+        F: FnMut(&L, &R, &L, &R) -> Keep,
+    {
+        self.reserve(other.len);
+        for (left, right) in other {
+            let by_left = self.get_left_key_value(&left);
+            // a pair that conflicts on both sides at once only needs to be resolved once
+            let by_right = self
+                .get_right_key_value(&right)
+                .filter(|_| by_left.map_or(true, |(_, existing)| *existing != right))
+                .map(|(existing_right, existing_left)| (existing_left, existing_right));
+
+            // every conflict is resolved, even once one has already chosen to keep its pair
+            let keep_other = by_left
+                .into_iter()
+                .chain(by_right)
+                .map(|(existing_left, existing_right)| {
+                    resolve(existing_left, existing_right, &left, &right) == Keep::KeepOther
+                })
+                .fold(true, |all, keep_other| all & keep_other);
+
+            if keep_other {
+                self.insert(left, right);
+            }
+        }
+    }
+
+    /// Consumes the map, returning a `HashMap` from the left keys to the right keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let hash_map = map.into_left_hashmap();
+    /// assert_eq!(Some(&5), hash_map.get("Hello"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn into_left_hashmap(self) -> HashMap<L, R> {
+        let mut output = HashMap::with_capacity(self.len);
+        output.extend(self);
+        output
+    }
+
+    /// Consumes the map, returning a `HashMap` from the right keys to the left keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let hash_map = map.into_right_hashmap();
+    /// assert_eq!(Some(&"Hello"), hash_map.get(&5));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn into_right_hashmap(self) -> HashMap<R, L> {
+        let mut output = HashMap::with_capacity(self.len);
+        output.extend(self.into_iter().map(|(left, right)| (right, left)));
+        output
+    }
+
+    /// Returns a cursor over every pair in the map, in an arbitrary order, that allows the right
+    /// key of each pair to be replaced as it is visited. See `RightSlot` for how replacements
+    /// work. Each slot borrows the map mutably, so this is not an `Iterator`: call `next` in a
+    /// `while let` loop instead.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// let mut slots = map.iter_mut_right();
+    /// while let Some(slot) = slots.next() {
+    ///     if *slot.left() == "Hello" {
+    ///         assert_eq!(Ok(5), slot.set(6));
+    ///     }
+    /// }
+    /// assert_eq!(Some(&"Hello"), map.get_right(&6));
+    /// ```
+    pub fn iter_mut_right(&mut self) -> IterMutRight<'_, L, R, LH, RH, B, I> {
+        IterMutRight::new(self)
+    }
+
+    /// Gets the entry for a given left key, for in-place manipulation. The key is only hashed
+    /// once, regardless of whether the entry is then read, removed or inserted into.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, LeftEntry};
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// match map.left_entry("Hello") {
+    ///     LeftEntry::Occupied(entry) => assert_eq!(&5, entry.get()),
+    ///     LeftEntry::Vacant(_) => unreachable!(),
+    /// }
+    ///
+    /// match map.left_entry("World") {
+    ///     LeftEntry::Occupied(_) => unreachable!(),
+    ///     LeftEntry::Vacant(entry) => assert_eq!(&7, entry.insert(7)),
+    /// }
+    /// assert_eq!(Some(&"World"), map.get_right(&7));
+    /// ```
+    pub fn left_entry(&mut self, left: L) -> LeftEntry<'_, L, R, LH, RH, B, I> {
+        let hash = Self::find_hash(&left, &self.left_hasher);
+        match Self::find_index(&left, hash, &self.left_data) {
+            Some(index) => LeftEntry::Occupied(OccupiedLeftEntry::new(self, index)),
+            None => LeftEntry::Vacant(VacantLeftEntry::new(self, left, hash)),
+        }
+    }
+
+    /// Gets the right value paired with a given left key. If the left key isn't in the map, `f` is
+    /// called to create a right value, and the new pair is inserted before the value is returned.
+    /// Just like `insert`, if the newly created right value is already paired with another left
+    /// key, that pair is evicted from the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(&5, map.get_left_or_insert_with("Hello", || unreachable!()));
+    /// assert_eq!(&7, map.get_left_or_insert_with("World", || 7));
+    ///
+    /// // the pair ("World", 7) is evicted, because 7 is now paired with "Goodbye"
+    /// assert_eq!(&7, map.get_left_or_insert_with("Goodbye", || 7));
+    /// assert_eq!(None, map.get_left("World"));
+    /// ```
+    pub fn get_left_or_insert_with<F: FnOnce() -> R>(&mut self, left: L, f: F) -> &R {
+        self.left_entry(left).or_insert_with(f)
+    }
+
+    /// Retains only the pairs specified by the predicate. In other words, removes every pair
+    /// `(left, right)` for which `f(&left, &right)` returns false. Both the left and right sides of
+    /// each removed pair are removed from the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u32, char> = (0..8).zip("abcdefgh".chars()).collect();
+    ///
+    /// map.retain(|&left, _| left % 2 == 0);
+    /// assert_eq!(4, map.len());
+    /// assert_eq!(Some(&'a'), map.get_left(&0));
+    /// assert_eq!(None, map.get_right(&'b'));
+    /// ```
+    pub fn retain<F: FnMut(&L, &R) -> bool>(&mut self, mut f: F) {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ..
+        } = self;
+
+        // removing a pair never moves any other pair, so it is safe to remove while walking
+        for index in 0..left_data.len() {
+            let keep = match left_data[index].data {
+                Some((ref left, right_index, ..)) => {
+                    let (ref right, ..) =
+                        *right_data[right_index.to_usize()].data.as_ref().unwrap();
+                    f(left, right)
+                }
+                None => true,
+            };
+            if !keep {
+                Self::remove_at(index, left_data, right_data, len);
+            }
+        }
+    }
+
+    /// Retains only the pairs whose left key matches the predicate. Both the left and right sides of
+    /// each removed pair are removed from the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u32, char> = (0..8).zip("abcdefgh".chars()).collect();
+    ///
+    /// map.retain_left(|&left| left % 2 == 0);
+    /// assert_eq!(4, map.len());
+    /// assert_eq!(Some(&'a'), map.get_left(&0));
+    /// assert_eq!(None, map.get_right(&'b'));
+    /// ```
+    pub fn retain_left<F: FnMut(&L) -> bool>(&mut self, mut f: F) {
+        self.retain(|left, _| f(left))
+    }
+
+    /// Retains only the pairs whose right key matches the predicate. Both the left and right sides
+    /// of each removed pair are removed from the map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u32, char> = (0..8).zip("abcdefgh".chars()).collect();
+    ///
+    /// map.retain_right(|&right| right < 'c');
+    /// assert_eq!(2, map.len());
+    /// assert_eq!(Some(&1), map.get_right(&'b'));
+    /// assert_eq!(None, map.get_left(&2));
+    /// ```
+    pub fn retain_right<F: FnMut(&R) -> bool>(&mut self, mut f: F) {
+        self.retain(|_, right| f(right))
+    }
+
+    /// Removes every pair that matches the predicate from the map, and returns them in a new map
+    /// that uses copies of this map's hashers and settings. The pairs that don't match are left in
+    /// this map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u32, char> = (0..8).zip("abcdefgh".chars()).collect();
+    ///
+    /// let odd = map.split_off(|&left, _| left % 2 == 1);
+    /// assert_eq!(4, map.len());
+    /// assert_eq!(4, odd.len());
+    /// assert_eq!(Some(&'a'), map.get_left(&0));
+    /// assert_eq!(Some(&'b'), odd.get_left(&1));
+    /// ```
+    pub fn split_off<F: FnMut(&L, &R) -> bool>(&mut self, mut pred: F) -> Self
+    where
+        LH: Clone,
+        RH: Clone,
+    {
+        // ask the predicate about every pair first, so that the new map can be allocated at its
+        // final size rather than grown as pairs move across. It never needs more buckets than
+        // this map already has.
+        let split: Vec<usize> = (0..self.left_data.len())
+            .filter(|&index| match self.left_data[index].data {
+                Some((ref left, right_index, ..)) => {
+                    let (ref right, ..) = *self.right_data[right_index.to_usize()]
+                        .data
+                        .as_ref()
+                        .unwrap();
+                    pred(left, right)
+                }
+                None => false,
+            })
+            .collect();
+        let capacity = cmp::min(
+            buckets_for::<B>(split.len(), self.load_factor),
+            self.left_data.len(),
+        );
+
+        let mut output = BiMap {
+            len: 0,
+            left_data: Bucket::empty_vec(capacity),
+            right_data: Bucket::empty_vec(capacity),
+            left_hasher: self.left_hasher.clone(),
+            right_hasher: self.right_hasher.clone(),
+            load_factor: self.load_factor,
+            growth_factor: self.growth_factor,
+            max_len: self.max_len,
+            pop_hint: 0,
+        };
+
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ..
+        } = self;
+
+        // as with `retain`, removing a pair never moves any other pair
+        for index in split {
+            // both maps hash the same way, so the stored hashes can be moved across as well
+            let (left, right_index, left_ideal, left_hash) = left_data[index].data.take().unwrap();
+            Self::mark_as_empty(left_ideal.to_usize(), index, left_data);
+            let right_index = right_index.to_usize();
+            let (right, _, right_ideal, right_hash) = right_data[right_index].data.take().unwrap();
+            Self::mark_as_empty(right_ideal.to_usize(), right_index, right_data);
+            *len -= 1;
+
+            output.insert_new(left, left_hash, right, right_hash);
+        }
+
+        output
+    }
+
+    /// Returns true if the map contains the given pair - that is, if the left key is in the map
+    /// and is paired with the right key.
+    fn contains_pair(&self, left: &L, right: &R) -> bool {
+        self.get_left(left).is_some_and(|paired| paired == right)
+    }
+
+    /// Returns true if the two maps have no pairs in common. Maps may still be disjoint if they
+    /// share left keys or right keys, as long as those keys are paired differently.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut a = BiMap::new();
+    /// a.insert("Hello", 5);
+    ///
+    /// let mut b = BiMap::new();
+    /// b.insert("Hello", 7);
+    /// assert!(a.is_disjoint(&b));
+    ///
+    /// b.insert("Hello", 5);
+    /// assert!(!a.is_disjoint(&b));
+    /// ```
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        let (smaller, larger) = if self.len <= other.len {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        smaller
+            .iter()
+            .all(|(left, right)| !larger.contains_pair(left, right))
+    }
+
+    /// Returns true if every pair in this map is also in `other`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut a = BiMap::new();
+    /// a.insert("Hello", 5);
+    ///
+    /// let mut b = a.clone();
+    /// b.insert("World", 7);
+    /// assert!(a.is_subset(&b));
+    /// assert!(!b.is_subset(&a));
+    /// ```
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len <= other.len
+            && self
+                .iter()
+                .all(|(left, right)| other.contains_pair(left, right))
+    }
+
+    /// Returns true if every pair in `other` is also in this map.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut a = BiMap::new();
+    /// a.insert("Hello", 5);
+    ///
+    /// let mut b = a.clone();
+    /// b.insert("World", 7);
+    /// assert!(b.is_superset(&a));
+    /// assert!(!a.is_superset(&b));
+    /// ```
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns an iterator over the pairs that are in both this map and `other`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut a = BiMap::new();
+    /// a.insert("Hello", 5);
+    /// a.insert("World", 7);
+    ///
+    /// let mut b = BiMap::new();
+    /// b.insert("Hello", 5);
+    /// b.insert("World", 8);
+    ///
+    /// let shared: Vec<_> = a.intersection(&b).collect();
+    /// assert_eq!(vec![(&"Hello", &5)], shared);
+    /// ```
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (&'a L, &'a R)> {
+        self.iter()
+            .filter(move |&(left, right)| other.contains_pair(left, right))
+    }
+
+    /// Returns an iterator over the pairs that are in this map, but not in `other`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut a = BiMap::new();
+    /// a.insert("Hello", 5);
+    /// a.insert("World", 7);
+    ///
+    /// let mut b = BiMap::new();
+    /// b.insert("Hello", 5);
+    /// b.insert("World", 8);
+    ///
+    /// let unshared: Vec<_> = a.difference(&b).collect();
+    /// assert_eq!(vec![(&"World", &7)], unshared);
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (&'a L, &'a R)> {
+        self.iter()
+            .filter(move |&(left, right)| !other.contains_pair(left, right))
+    }
+
+    /// Returns the largest number of keys that share an ideal position within the map, on either
+    /// side. This is bounded by the size of the bitfield, and is intended as a diagnostic for how
+    /// well the hashers are distributing keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(0, map.max_neighbourhood_occupancy());
+    ///
+    /// map.insert("Hello", 5);
+    /// assert_eq!(1, map.max_neighbourhood_occupancy());
+    /// ```
+    pub fn max_neighbourhood_occupancy(&self) -> usize {
+        self.left_data
+            .iter()
+            .map(|bucket| bucket.neighbourhood.count_ones())
+            .chain(
+                self.right_data
+                    .iter()
+                    .map(|bucket| bucket.neighbourhood.count_ones()),
+            )
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Reports how far the keys in the map have been displaced from their ideal positions, for
+    /// each side of the map. Large offsets mean that the hashers are clustering keys together, and
+    /// offsets close to the size of the bitfield mean that a wider bitfield may avoid resizes.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let stats = map.probe_stats();
+    /// assert!(stats.left_max < 32);
+    /// assert!(stats.left_mean <= stats.left_max as f32);
+    /// ```
+    pub fn probe_stats(&self) -> ProbeStats {
+        fn side<K, B, I: PairIndex>(data: &[Bucket<K, I, B>]) -> (usize, f32) {
+            let mask = data.len().wrapping_sub(1);
+            let (count, total, max) = data
+                .iter()
+                .enumerate()
+                .filter_map(|(index, bucket)| {
+                    bucket
+                        .data
+                        .as_ref()
+                        .map(|&(_, _, ideal, _)| index.wrapping_sub(ideal.to_usize()) & mask)
+                })
+                .fold((0, 0, 0), |(count, total, max), offset| {
+                    (count + 1, total + offset, cmp::max(max, offset))
+                });
+            let mean = if count == 0 {
+                0.0
+            } else {
+                total as f32 / count as f32
+            };
+            (max, mean)
+        }
+
+        let (left_max, left_mean) = side(&self.left_data);
+        let (right_max, right_mean) = side(&self.right_data);
+        ProbeStats {
+            left_max,
+            left_mean,
+            right_max,
+            right_mean,
+        }
+    }
+
+    /// Reports how far a single left key has been displaced from its ideal position, or `None` if
+    /// the key is not in the map. This is the per-key version of `probe_stats`, and is always less
+    /// than the size of the bitfield.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert!(map.left_probe_offset("Hello").unwrap() < 32);
+    /// assert_eq!(None, map.left_probe_offset("World"));
+    /// ```
+    pub fn left_probe_offset<Q>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = Self::find_hash(&left, &self.left_hasher);
+        let index = Self::find_index(left, hash, &self.left_data)?;
+        let &(_, _, ideal, _) = self.left_data[index].data.as_ref().unwrap();
+        Some((self.left_data.len() + index - ideal.to_usize()) & (self.left_data.len() - 1))
+    }
+
+    /// Checks that the internal structure of the hashmap is consistent, panicking if it is not.
+    #[cfg(test)]
+    fn invariants(&self) {
+        fn check<K, V, H, B, I>(
+            key_data: &[Bucket<K, I, B>],
+            value_data: &[Bucket<V, I, B>],
+            hasher: &H,
+        ) -> usize
+        where
+            K: Hash,
+            H: BuildHasher,
+            B: BitField,
+            I: PairIndex,
+        {
+            let len = key_data.len();
+            assert!(len == 0 || len >= B::size());
+            let mut count = 0;
+            for (index, bucket) in key_data.iter().enumerate() {
+                if let Some((ref key, value_index, ideal, hash)) = bucket.data {
+                    let (value_index, ideal) = (value_index.to_usize(), ideal.to_usize());
+                    count += 1;
+                    assert_eq!(hash, hasher.hash_one(key));
+                    assert!(len.is_power_of_two());
+                    assert_eq!(ideal, hash as usize & (len - 1));
+                    let offset = (len + index - ideal) & (len - 1);
+                    assert!(offset < B::size());
+                    assert!(key_data[ideal].neighbourhood.iter().any(|o| o == offset));
+                    let &(_, paired_index, ..) = value_data[value_index].data.as_ref().unwrap();
+                    assert_eq!(paired_index.to_usize(), index);
+                }
+                for offset in bucket.neighbourhood.iter() {
+                    let &(_, _, ideal, _) = key_data[(index + offset) & (len - 1)]
+                        .data
+                        .as_ref()
+                        .unwrap();
+                    assert_eq!(ideal.to_usize(), index);
+                }
+            }
+            count
+        }
+
+        assert_eq!(self.left_data.len(), self.right_data.len());
+        let left_count = check(&self.left_data, &self.right_data, &self.left_hasher);
+        let right_count = check(&self.right_data, &self.left_data, &self.right_hasher);
+        assert_eq!(self.len, left_count);
+        assert_eq!(self.len, right_count);
+    }
+}
+
+impl<L, R, LH, RH, B, I> BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Replaces both hashers with freshly created ones, and re-inserts every pair into new backing
+    /// arrays of the same capacity. With a randomly seeded hasher such as `RandomState`, this
+    /// re-seeds the map, which can be used to recover from a run of deliberately colliding keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// let before = map.clone();
+    ///
+    /// map.rehash();
+    /// assert_eq!(before, map);
+    /// ```
+    pub fn rehash(&mut self) {
+        let capacity = self.left_data.len();
+        self.len = 0;
+        self.left_hasher = LH::default();
+        self.right_hasher = RH::default();
+        let old_left_data = mem::replace(&mut self.left_data, Bucket::empty_vec(capacity));
+        let mut old_right_data = mem::replace(&mut self.right_data, Bucket::empty_vec(capacity));
+
+        // the hashers have changed, so unlike `resize` the stored hashes must be recomputed
+        for bucket in old_left_data.into_vec() {
+            if let Some((left, right_index, ..)) = bucket.data {
+                let (right, ..) = old_right_data[right_index.to_usize()].data.take().unwrap();
+                let left_hash = Self::find_hash(&left, &self.left_hasher);
+                let right_hash = Self::find_hash(&right, &self.right_hasher);
+                self.insert_new(left, left_hash, right, right_hash);
+            }
+        }
+    }
+}
+
+impl<L, R, LH, RH, B, I> Clone for BiMap<L, R, LH, RH, B, I>
+where
+    L: Clone,
+    R: Clone,
+    LH: Clone,
+    RH: Clone,
+    B: Clone,
+    I: Clone,
+{
+    fn clone(&self) -> Self {
+        BiMap {
+            len: self.len,
+            left_data: self.left_data.clone(),
+            right_data: self.right_data.clone(),
+            left_hasher: self.left_hasher.clone(),
+            right_hasher: self.right_hasher.clone(),
+            load_factor: self.load_factor,
+            growth_factor: self.growth_factor,
+            max_len: self.max_len,
+            pop_hint: self.pop_hint,
+        }
+    }
+
+    /// Overwrites this map with a copy of `source`. If both maps have the same capacity, the
+    /// buckets are copied across in place and the existing backing arrays are reused, rather than
+    /// being freed and reallocated. Either way, this map ends up laid out exactly like `source`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut source = BiMap::with_capacity(100);
+    /// source.insert("Hello", 5);
+    ///
+    /// let mut copy = BiMap::with_capacity(100);
+    /// copy.insert("World", 7);
+    /// copy.clone_from(&source);
+    /// assert_eq!(source, copy);
+    /// ```
+    fn clone_from(&mut self, source: &Self) {
+        if self.left_data.len() == source.left_data.len() {
+            self.left_data.clone_from_slice(&source.left_data);
+            self.right_data.clone_from_slice(&source.right_data);
+        } else {
+            self.left_data = source.left_data.clone();
+            self.right_data = source.right_data.clone();
+        }
+
+        self.len = source.len;
+        self.left_hasher.clone_from(&source.left_hasher);
+        self.right_hasher.clone_from(&source.right_hasher);
+        self.load_factor = source.load_factor;
+        self.growth_factor = source.growth_factor;
+        self.max_len = source.max_len;
+        self.pop_hint = source.pop_hint;
+    }
+}
+
+impl<L, R, LH, RH, B, I> PartialEq for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // once the lengths match, finding every pair of this map in the other means that the other
+        // can't hold any extra pairs either, so there is no need to look the right keys up as well
+        self.len == other.len
+            && self.iter().all(|(left, right)| {
+                Self::get(
+                    left,
+                    &other.left_data,
+                    &other.right_data,
+                    &other.left_hasher,
+                )
+                .is_some_and(|(_, other_right)| right == other_right)
+            })
+    }
+}
+
+impl<L, R, LH, RH, B, I> Eq for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+}
+
+impl<L, R, LH, RH, B, I> PartialOrd for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Ord,
+    R: Hash + Ord,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Maps are ordered by comparing their pairs, sorted by left key, lexicographically - the same way
+/// two sorted `Vec<(L, R)>`s would be. This doesn't depend on the order in which the pairs are
+/// stored, so it agrees with `Eq`, but it does mean collecting and sorting the pairs of both maps,
+/// which allocates and takes O(n log n) time.
+///
+/// ```
+/// # use isomorphism::BiMap;
+/// let mut small = BiMap::new();
+/// small.insert(1, 'a');
+///
+/// let mut large = BiMap::new();
+/// large.insert(1, 'b');
+///
+/// assert!(small < large);
+/// ```
+impl<L, R, LH, RH, B, I> Ord for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Ord,
+    R: Hash + Ord,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.iter_sorted_by_left().cmp(other.iter_sorted_by_left())
+    }
+}
+
+impl<L, R, LH, RH, B, I> BiMap<L, R, LH, RH, B, I>
+where
+    L: Debug,
+    R: Debug,
+    I: PairIndex,
+{
+    /// Writes the map out as its pairs, prefixed with a given type name. The pretty form also
+    /// labels which side of each pair is which, as that can't be told apart when `L` and `R` are
+    /// the same type.
+    pub(crate) fn fmt_named(&self, name: &str, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{} (left: right) ", name)?;
+        } else {
+            write!(f, "{} ", name)?;
+        }
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the map as `BiMap` followed by its pairs, written as a map from left keys to right
+/// keys.
+///
+/// ```
+/// # use isomorphism::BiMap;
+/// let mut map = BiMap::new();
+/// map.insert("Hello", 5);
+///
+/// assert_eq!(r#"BiMap {"Hello": 5}"#, format!("{:?}", map));
+/// assert_eq!("BiMap (left: right) {\n    \"Hello\": 5,\n}", format!("{:#?}", map));
+/// ```
+impl<L, R, LH, RH, B, I> Debug for BiMap<L, R, LH, RH, B, I>
+where
+    L: Debug,
+    R: Debug,
+    I: PairIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_named("BiMap", f)
+    }
+}
+
+/// Indexing a `BiMap` looks up a left key, returning the right value it is paired with. To look up
+/// a right key, use the `get_right` method.
+///
+/// ```
+/// # use isomorphism::BiMap;
+/// let mut map = BiMap::new();
+/// map.insert("Hello", 5);
+///
+/// assert_eq!(5, map[&"Hello"]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the left key is not present in the map.
+impl<L, R, LH, RH, B, I, Q> Index<&Q> for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq + Borrow<Q>,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+    Q: ?Sized + Hash + Eq,
+{
+    type Output = R;
+
+    fn index(&self, left: &Q) -> &R {
+        self.get_left(left).expect("no entry found for left key")
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I: PairIndex> IntoIterator for &'a BiMap<L, R, LH, RH, B, I> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R, B, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let BiMap {
+            len,
+            left_data,
+            right_data,
+            ..
+        } = self;
+        Iter::new(left_data.iter(), right_data, *len)
+    }
+}
+
+impl<L, R, LH, RH, B, I: PairIndex> IntoIterator for BiMap<L, R, LH, RH, B, I> {
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R, B, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let BiMap {
+            len,
+            left_data,
+            right_data,
+            ..
+        } = self;
+        IntoIter::new(left_data, right_data, len)
+    }
+}
+
+impl<L, R, LH, RH, B, I> FromIterator<(L, R)> for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        let mut output = BiMapBuilder::with_hashers(Default::default(), Default::default())
+            .bitfield::<B>()
+            .pair_index::<I>()
+            .finish();
+        output.extend(iter);
+        output
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I> FromIterator<(&'a L, &'a R)> for BiMap<L, R, LH, RH, B, I>
+where
+    L: Copy + Hash + Eq + 'a,
+    R: Copy + Hash + Eq + 'a,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    fn from_iter<T: IntoIterator<Item = (&'a L, &'a R)>>(iter: T) -> Self {
+        let mut output = BiMapBuilder::with_hashers(Default::default(), Default::default())
+            .bitfield::<B>()
+            .pair_index::<I>()
+            .finish();
+        output.extend(iter);
+        output
+    }
+}
+
+/// Creates a `BiMap` from an array of pairs. The pairs are inserted in order, so just like
+/// `insert`, if a left or right key appears more than once then the last pair containing it wins.
+///
+/// ```
+/// # use isomorphism::BiMap;
+/// let map = BiMap::from([("Hello", 5), ("World", 7), ("Goodbye", 5)]);
+///
+/// assert_eq!(2, map.len());
+/// assert_eq!(None, map.get_left("Hello"));
+/// assert_eq!(Some(&"Goodbye"), map.get_right(&5));
+/// ```
+#[cfg(feature = "std")]
+impl<L, R, const N: usize> From<[(L, R); N]> for BiMap<L, R>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+{
+    fn from(pairs: [(L, R); N]) -> Self {
+        let mut output = BiMap::with_capacity(N);
+        for (left, right) in pairs {
+            output.insert(left, right);
+        }
+        output
+    }
+}
+
+/// The error returned when a `HashMap` can't be converted into a `BiMap`, because two different
+/// keys in the `HashMap` map to the same value. Holds the value that appeared more than once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateRightError<R>(pub R);
+
+impl<R: Debug> fmt::Display for DuplicateRightError<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value {:?} is mapped to by more than one key",
+            self.0
+        )
+    }
+}
+
+impl<R: Debug> Error for DuplicateRightError<R> {}
+
+/// The position of a pair within a `BiMap`, returned by `BiMap::left_handle` and
+/// `BiMap::right_handle`. Passing it to `BiMap::resolve` gets the pair back without hashing or
+/// comparing any keys. A handle is only valid until the map is next modified, as inserting or
+/// removing pairs can move the others around.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle(usize);
+
+/// The error returned by `BiMap::try_get_left` and `BiMap::try_get_right` when the key being looked
+/// up is not in the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyNotFound;
+
+impl fmt::Display for KeyNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("key not found in bimap")
+    }
+}
+
+impl Error for KeyNotFound {}
+
+/// The error returned by `BiMap::try_reserve` when the map could not be grown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The number of buckets needed, or the memory needed to hold them, does not fit in a `usize`.
+    CapacityOverflow,
+    /// The allocator was unable to provide the memory needed.
+    AllocError,
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => f.write_str("capacity overflow"),
+            TryReserveError::AllocError => f.write_str("memory allocation failed"),
+        }
+    }
+}
+
+impl Error for TryReserveError {}
+
+/// Converts a `HashMap` into a `BiMap`, failing if any two keys in the `HashMap` map to the same
+/// value.
+///
+/// ```
+/// # use isomorphism::{BiMap, DuplicateRightError};
+/// use std::collections::HashMap;
+/// use std::convert::TryFrom;
+///
+/// let mut hash_map = HashMap::new();
+/// hash_map.insert("Hello", 5);
+/// hash_map.insert("Goodbye", 6);
+/// let map = BiMap::try_from(hash_map.clone()).unwrap();
+/// assert_eq!(Some(&"Hello"), map.get_right(&5));
+///
+/// hash_map.insert("Hi", 5);
+/// assert_eq!(Err(DuplicateRightError(5)), BiMap::try_from(hash_map));
+/// ```
+#[cfg(feature = "std")]
+impl<L, R, S> TryFrom<HashMap<L, R, S>> for BiMap<L, R>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+{
+    type Error = DuplicateRightError<R>;
+
+    fn try_from(pairs: HashMap<L, R, S>) -> Result<Self, Self::Error> {
+        let mut output = BiMap::with_capacity(pairs.len());
+        for (left, right) in pairs {
+            output
+                .try_insert(left, right)
+                .map_err(|(_left, right)| DuplicateRightError(right))?;
+        }
+        Ok(output)
+    }
+}
+
+impl<L, R, LH, RH, B, I> Extend<(L, R)> for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        for (left, right) in iter {
+            self.insert(left, right);
+        }
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I> Extend<(&'a L, &'a R)> for BiMap<L, R, LH, RH, B, I>
+where
+    L: Copy + Hash + Eq + 'a,
+    R: Copy + Hash + Eq + 'a,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+    I: PairIndex,
+{
+    fn extend<T: IntoIterator<Item = (&'a L, &'a R)>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().map(|(&left, &right)| (left, right)));
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<L, R, LH, RH, B, I> Serialize for BiMap<L, R, LH, RH, B, I>
+where
+    L: Serialize,
+    R: Serialize,
+    I: PairIndex,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(Some(self.len))?;
+        for pair in self.iter() {
+            seq.serialize_element(&pair)?;
+        }
+
+        seq.end()
+    }
+}
+
+/// A wrapper that serializes a `BiMap` as a map from left keys to right keys, rather than as a
+/// sequence of pairs. Formats such as JSON only allow string keys in maps, so for those formats
+/// `L` must serialize to something string-like. The output can be read back in with the regular
+/// `Deserialize` implementation of `BiMap`.
+///
+/// ```
+/// # use isomorphism::{AsMap, BiMap};
+/// let mut map = BiMap::new();
+/// map.insert("Hello".to_owned(), 1);
+///
+/// let json = serde_json::to_string(&AsMap(&map)).unwrap();
+/// assert_eq!(r#"{"Hello":1}"#, json);
+///
+/// let round_trip: BiMap<String, u32> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(map, round_trip);
+/// ```
+#[cfg(feature = "serde")]
+pub struct AsMap<
+    'a,
+    L,
+    R,
+    LH = DefaultHashBuilder,
+    RH = DefaultHashBuilder,
+    B = DefaultBitField,
+    I = DefaultPairIndex,
+>(pub &'a BiMap<L, R, LH, RH, B, I>);
+
+#[cfg(feature = "serde")]
+impl<'a, L, R, LH, RH, B, I> Serialize for AsMap<'a, L, R, LH, RH, B, I>
+where
+    L: Serialize,
+    R: Serialize,
+    I: PairIndex,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let &AsMap(map) = self;
+        let mut output = serializer.serialize_map(Some(map.len))?;
+        for (left, right) in map.iter() {
+            output.serialize_entry(left, right)?;
+        }
+
+        output.end()
+    }
+}
+
+/// A wrapper that serializes a `BiMap` as a sequence of pairs sorted by left key, in the same shape
+/// as the regular `Serialize` implementation. The regular implementation writes the pairs out in
+/// whatever order they are stored in, which depends on the hasher, so equal maps can serialize
+/// differently. Sorting them first means that equal maps always serialize to the same output.
+///
+/// Wrapping a `BiMap` in this type also allows it to be deserialized from a sequence of pairs -
+/// whether or not they are sorted - which will fail if any left key or right key is repeated.
+///
+/// ```
+/// # use isomorphism::{AsSortedSeq, BiMap};
+/// let mut map = BiMap::new();
+/// map.insert(2, 'b');
+/// map.insert(1, 'a');
+///
+/// let json = serde_json::to_string(&AsSortedSeq(&map)).unwrap();
+/// assert_eq!(r#"[[1,"a"],[2,"b"]]"#, json);
+///
+/// let AsSortedSeq(round_trip): AsSortedSeq<BiMap<u32, char>> =
+///     serde_json::from_str(&json).unwrap();
+/// assert_eq!(map, round_trip);
+/// ```
+#[cfg(feature = "serde")]
+pub struct AsSortedSeq<T>(pub T);
+
+#[cfg(feature = "serde")]
+impl<L, R, LH, RH, B, I> Serialize for AsSortedSeq<&BiMap<L, R, LH, RH, B, I>>
+where
+    L: Ord + Serialize,
+    R: Serialize,
+    I: PairIndex,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let &AsSortedSeq(map) = self;
+        let mut seq = serializer.serialize_seq(Some(map.len))?;
+        for pair in map.iter_sorted_by_left() {
+            seq.serialize_element(&pair)?;
+        }
+
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L, R, LH, RH, B, I> Deserialize<'de> for AsSortedSeq<BiMap<L, R, LH, RH, B, I>>
+where
+    L: Hash + Eq + Deserialize<'de>,
+    R: Hash + Eq + Deserialize<'de>,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_seq(MapVisitor::new(true))
+            .map(AsSortedSeq)
+    }
+}
+
+/// Reads in a `BiMap` from a map of left keys to right keys. If the same left key or right key
+/// appears more than once, deserialization fails with a "duplicate key in bimap" error rather than
+/// silently dropping pairs. See `Lenient` for a way to opt out of this check. Pairs that can't be
+/// placed in the map, because the pair index type can't count enough buckets or because too many
+/// keys share a hash, fail deserialization rather than panicking.
+///
+/// ```
+/// # use isomorphism::BiMap;
+/// let map: Result<BiMap<String, u32>, _> = serde_json::from_str(r#"{"a":1,"b":1}"#);
+/// assert!(map.is_err());
+/// ```
+#[cfg(feature = "serde")]
+impl<'de, L, R, LH, RH, B, I> Deserialize<'de> for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq + Deserialize<'de>,
+    R: Hash + Eq + Deserialize<'de>,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MapVisitor::new(true))
+    }
+}
+
+/// A wrapper that deserializes a `BiMap` without checking for duplicate keys. Each pair is
+/// inserted in turn, so later pairs replace any earlier pairs that they conflict with - the same
+/// behaviour as collecting the pairs with `FromIterator`.
+///
+/// ```
+/// # use isomorphism::{BiMap, Lenient};
+/// let Lenient(map): Lenient<BiMap<String, u32>> =
+///     serde_json::from_str(r#"{"a":1,"b":1}"#).unwrap();
+/// assert_eq!(1, map.len());
+/// assert_eq!(Some(&1), map.get_left("b"));
+/// ```
+#[cfg(feature = "serde")]
+pub struct Lenient<T>(pub T);
+
+#[cfg(feature = "serde")]
+impl<'de, L, R, LH, RH, B, I> Deserialize<'de> for Lenient<BiMap<L, R, LH, RH, B, I>>
+where
+    L: Hash + Eq + Deserialize<'de>,
+    R: Hash + Eq + Deserialize<'de>,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_map(MapVisitor::new(false))
+            .map(Lenient)
+    }
+}
+
+/// The most pairs that a deserializer's `size_hint` can reserve space for up front. The hint comes
+/// from the input, so a hostile payload could otherwise claim an enormous length and have a huge
+/// map allocated before a single pair has been read - past this, the map grows as pairs arrive.
+#[cfg(feature = "serde")]
+const MAX_PREALLOCATED_PAIRS: usize = 4096;
+
+/// Visitor shared by the strict and lenient `Deserialize` implementations.
+#[cfg(feature = "serde")]
+struct MapVisitor<L, R, LH, RH, B, I> {
+    /// Should duplicate keys be reported as errors?
+    strict: bool,
+    marker: core::marker::PhantomData<BiMap<L, R, LH, RH, B, I>>,
+}
+
+#[cfg(feature = "serde")]
+impl<L, R, LH, RH, B, I> MapVisitor<L, R, LH, RH, B, I> {
+    fn new(strict: bool) -> Self {
+        MapVisitor {
+            strict,
+            marker: core::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<L, R, LH, RH, B, I> MapVisitor<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Builds a map out of the pairs returned by `next`, which reads either map entries or
+    /// sequence elements depending on what is being visited.
+    fn collect<E, F>(
+        self,
+        size_hint: Option<usize>,
+        mut next: F,
+    ) -> Result<BiMap<L, R, LH, RH, B, I>, E>
+    where
+        E: serde::de::Error,
+        F: FnMut() -> Result<Option<(L, R)>, E>,
+    {
+        let strict = self.strict;
+        let insert = |output: &mut BiMap<L, R, LH, RH, B, I>, left: L, right: R| {
+            if strict && (output.contains_left(&left) || output.contains_right(&right)) {
+                return Err(E::custom("duplicate key in bimap"));
+            }
+            // the map has no max_len, so this only fails once the pair index type runs out of
+            // buckets, or a neighbourhood fills up with keys of the same hash. Neither is something
+            // untrusted input should be able to turn into a panic
+            output.insert_bounded(left, right).map(|_| ()).map_err(|_| {
+                match output.grown_capacity() {
+                    Some(_) => E::custom("too many keys with the same hash in bimap"),
+                    None => E::custom("too many pairs for the pair index type"),
+                }
+            })
+        };
+
+        let builder = BiMapBuilder::with_hashers(Default::default(), Default::default())
+            .bitfield::<B>()
+            .pair_index::<I>();
+        match size_hint {
+            Some(size) => {
+                let capacity = cmp::min(size, MAX_PREALLOCATED_PAIRS);
+                let mut output = builder.capacity(capacity).finish();
+                while let Some((left, right)) = next()? {
+                    insert(&mut output, left, right)?;
+                }
+                Ok(output)
+            }
+            None => {
+                // without a length up front, growing the map as pairs arrive would rehash every
+                // pair each time it resized, so buffer the pairs and allocate the map once instead
+                let mut pairs = Vec::new();
+                while let Some(pair) = next()? {
+                    pairs.push(pair);
+                }
+
+                // half the largest table holds that many pairs at the default load factor, so
+                // never ask for more than that up front
+                let capacity = cmp::min(pairs.len(), pair_index::max_buckets::<I>() / 2);
+                let mut output = builder.capacity(capacity).finish();
+                for (left, right) in pairs {
+                    insert(&mut output, left, right)?;
+                }
+                Ok(output)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L, R, LH, RH, B, I> serde::de::Visitor<'de> for MapVisitor<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq + Deserialize<'de>,
+    R: Hash + Eq + Deserialize<'de>,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+    I: PairIndex,
+{
+    type Value = BiMap<L, R, LH, RH, B, I>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map or a sequence of pairs")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let size_hint = map.size_hint();
+        self.collect(size_hint, || map.next_entry())
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let size_hint = seq.size_hint();
+        self.collect(size_hint, || seq.next_element())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bitfield::{BitField, WideBitField};
+    use crate::{BiMap, BiMapBuilder, LeftEntry};
+
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasherDefault, Hasher};
+    use std::mem;
+
+    /// A deliberately poor hasher, which sends runs of four consecutive integers to the same
+    /// bucket so that the hopscotch displacement logic gets exercised.
+    #[derive(Default)]
+    struct ClusteringHasher(u64);
+
+    impl Hasher for ClusteringHasher {
+        fn finish(&self) -> u64 {
+            (self.0 / 4).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 << 8) | u64::from(byte);
+            }
+        }
+
+        fn write_usize(&mut self, value: usize) {
+            self.0 = value as u64;
+        }
+    }
+
+    type Clustering = BuildHasherDefault<ClusteringHasher>;
+
+    /// A hasher that gives every key the same hash.
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            0x9E37_79B9_7F4A_7C15
+        }
+
+        fn write(&mut self, _bytes: &[u8]) {}
+    }
+
+    type Constant = BuildHasherDefault<ConstantHasher>;
+
+    /// A hasher that uses integer keys as their own hashes, so that consecutive keys fill
+    /// consecutive buckets without ever colliding.
+    #[derive(Default)]
+    struct IdentityHasher(u64);
+
+    impl Hasher for IdentityHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 << 8) | u64::from(byte);
+            }
+        }
+
+        fn write_usize(&mut self, value: usize) {
+            self.0 = value as u64;
+        }
+    }
+
+    type Identity = BuildHasherDefault<IdentityHasher>;
+
+    /// Fills a map built with the given load factor until it resizes, returning the number of
+    /// buckets before the resize and the number of pairs it held at that point.
+    fn fill_until_resize(load_factor: f32) -> (usize, usize) {
+        let mut map: BiMap<usize, usize, Identity, Identity> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .load_factor(load_factor)
+                .finish();
+        let buckets = map.left_data.len();
+
+        for i in 0.. {
+            map.insert(i, i);
+            map.invariants();
+            if map.left_data.len() != buckets {
+                return (buckets, i);
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn load_factor_half() {
+        // 32 pairs at a load factor of 0.5 need 64 buckets, which are then resized on the 33rd
+        let (buckets, len) = fill_until_resize(0.5);
+        assert_eq!(64, buckets);
+        assert_eq!(32, len);
+    }
+
+    #[test]
+    fn load_factor_ninety_percent() {
+        // 32 pairs at a load factor of 0.9 need 36 buckets, rounded up to 64, which can hold
+        // pairs until 0.9 * 64 = 57.6 of them are full
+        let (buckets, len) = fill_until_resize(0.9);
+        assert_eq!(64, buckets);
+        assert_eq!(58, len);
+    }
+
+    /// Fills a map until it resizes, returning the number of buckets before and after.
+    fn grow_once(mut map: BiMap<usize, usize, Identity, Identity>) -> (usize, usize) {
+        let buckets = map.left_data.len();
+        for i in 0.. {
+            map.insert(i, i);
+            if map.left_data.len() != buckets {
+                map.invariants();
+                return (buckets, map.left_data.len());
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn growth_factor_four() {
+        let builder = || {
+            BiMapBuilder::new()
+                .left_hasher(Default::default())
+                .right_hasher(Default::default())
+        };
+
+        assert_eq!((64, 128), grow_once(builder().finish()));
+        assert_eq!((64, 256), grow_once(builder().growth_factor(4).finish()));
+        // an empty map allocates the default 32 buckets, and overflowing those grows it to 128
+        let mut map = builder().capacity(0).growth_factor(4).finish();
+        map.insert(usize::MAX, usize::MAX);
+        assert_eq!((32, 128), grow_once(map));
+    }
+
+    #[test]
+    fn probe_stats_collision() {
+        let mut map: BiMap<usize, usize, Identity, Identity> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+        assert_eq!(64, map.left_data.len());
+
+        // 0 and 64 share an ideal position on the left, so one of them is pushed along by one
+        map.insert(0, 0);
+        map.insert(64, 1);
+        map.insert(5, 5);
+
+        let stats = map.probe_stats();
+        assert_eq!(1, stats.left_max);
+        assert_eq!(1.0 / 3.0, stats.left_mean);
+        assert_eq!(0, stats.right_max);
+        assert_eq!(0.0, stats.right_mean);
+
+        map.clear();
+        let stats = map.probe_stats();
+        assert_eq!((0, 0.0), (stats.left_max, stats.left_mean));
+    }
+
+    #[test]
+    fn left_probe_offset_collision() {
+        let mut map: BiMap<usize, usize, Identity, Identity> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+        let len = map.left_data.len();
+
+        // 1 and 1 + len share an ideal position, so whichever comes second is pushed along by one,
+        // and 2 then has to go past both of them
+        map.insert(1, 0);
+        map.insert(1 + len, 1);
+        map.insert(2, 2);
+
+        assert_eq!(Some(0), map.left_probe_offset(&1));
+        assert_eq!(Some(1), map.left_probe_offset(&(1 + len)));
+        assert_eq!(Some(1), map.left_probe_offset(&2));
+        assert_eq!(None, map.left_probe_offset(&3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn growth_factor_one() {
+        BiMapBuilder::new().growth_factor(1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn load_factor_zero() {
+        BiMapBuilder::new().load_factor(0.0);
+    }
+
+    quickcheck! {
+        fn retain_all(inputs: Vec<(usize, char)>) -> bool {
+            let mut map: BiMap<_, _> = inputs.into_iter().collect();
+            let expected = map.clone();
+
+            map.retain(|_, _| true);
+            map.invariants();
+            map == expected
+        }
+    }
+
+    quickcheck! {
+        fn retain_none(inputs: Vec<(usize, char)>) -> bool {
+            let mut map: BiMap<_, _> = inputs.into_iter().collect();
+
+            map.retain(|_, _| false);
+            map.invariants();
+            map.is_empty() && map.iter().next().is_none()
+        }
+    }
+
+    quickcheck! {
+        fn retain_some(inputs: Vec<(usize, char)>) -> bool {
+            let mut map: BiMap<_, _> = inputs.into_iter().collect();
+            let expected: BiMap<_, _> = map
+                .iter()
+                .filter(|&(&left, _)| left % 3 != 0)
+                .map(|(&left, &right)| (left, right))
+                .collect();
+
+            map.retain(|&left, _| left % 3 != 0);
+            map.invariants();
+            map == expected
+        }
+    }
+
+    quickcheck! {
+        fn retain_left_even(inputs: Vec<(usize, char)>) -> bool {
+            let mut map: BiMap<_, _> = inputs.into_iter().collect();
+            let expected: BiMap<_, _> = map
+                .iter()
+                .filter(|&(&left, _)| left % 2 == 0)
+                .map(|(&left, &right)| (left, right))
+                .collect();
+
+            map.retain_left(|left| *left % 2 == 0);
+            map.invariants();
+            map == expected
+        }
+    }
+
+    quickcheck! {
+        fn retain_right_some(inputs: Vec<(usize, char)>) -> bool {
+            let mut map: BiMap<_, _> = inputs.into_iter().collect();
+            let expected: BiMap<_, _> = map
+                .iter()
+                .filter(|&(_, right)| right.is_alphanumeric())
+                .map(|(&left, &right)| (left, right))
+                .collect();
+
+            map.retain_right(|right| right.is_alphanumeric());
+            map.invariants();
+            map == expected
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    quickcheck! {
+        fn arbitrary_maps_are_valid(map: BiMap<u8, u8>) -> bool {
+            use quickcheck::Arbitrary;
+
+            map.invariants();
+            map.shrink().all(|smaller| {
+                smaller.invariants();
+                smaller.len() <= map.len()
+            })
+        }
+    }
 
     #[test]
     fn test_iteration_empty() {
@@ -773,4 +3885,747 @@ mod test {
         assert_eq!((&map).into_iter().next(), None);
         assert_eq!(map.into_iter().next(), None);
     }
+
+    #[test]
+    fn test_left_entry_occupied() {
+        let mut map = BiMap::new();
+        map.insert("Hello", 5);
+        map.insert("World", 7);
+
+        match map.left_entry("Hello") {
+            LeftEntry::Occupied(entry) => {
+                assert_eq!(&"Hello", entry.key());
+                assert_eq!(&5, entry.get());
+                assert_eq!(5, entry.remove());
+            }
+            LeftEntry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+
+        map.invariants();
+        assert_eq!(1, map.len());
+        assert_eq!(None, map.get_left("Hello"));
+        assert_eq!(None, map.get_right(&5));
+    }
+
+    #[test]
+    fn test_left_entry_vacant() {
+        let mut map = BiMap::new();
+        map.insert("Hello", 5);
+
+        match map.left_entry("World") {
+            LeftEntry::Occupied(_) => panic!("expected a vacant entry"),
+            LeftEntry::Vacant(entry) => {
+                assert_eq!(&"World", entry.key());
+                assert_eq!(&7, entry.insert(7));
+            }
+        }
+
+        map.invariants();
+        assert_eq!(2, map.len());
+        assert_eq!(Some(&7), map.get_left("World"));
+        assert_eq!(Some(&"World"), map.get_right(&7));
+    }
+
+    #[test]
+    fn test_left_entry_vacant_evicts_right() {
+        let mut map = BiMap::new();
+        map.insert("Hello", 5);
+
+        match map.left_entry("World") {
+            LeftEntry::Occupied(_) => panic!("expected a vacant entry"),
+            LeftEntry::Vacant(entry) => {
+                entry.insert(5);
+            }
+        }
+
+        map.invariants();
+        assert_eq!(1, map.len());
+        assert_eq!(None, map.get_left("Hello"));
+        assert_eq!(Some(&5), map.get_left("World"));
+        assert_eq!(Some(&"World"), map.get_right(&5));
+    }
+
+    quickcheck! {
+        fn left_entry_insert_matches_insert(inputs: Vec<(usize, char)>) -> bool {
+            let mut expected = BiMap::new();
+            let mut map = BiMap::new();
+
+            for (a, b) in inputs {
+                expected.insert(a, b);
+                match map.left_entry(a) {
+                    LeftEntry::Occupied(entry) => {
+                        entry.remove();
+                        map.insert(a, b);
+                    }
+                    LeftEntry::Vacant(entry) => {
+                        entry.insert(b);
+                    }
+                }
+                map.invariants();
+            }
+
+            map == expected
+        }
+    }
+
+    quickcheck! {
+        fn pop_keeps_invariants(inputs: Vec<(usize, usize)>) -> bool {
+            let mut map: BiMap<usize, usize, Clustering, Clustering> =
+                BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+            map.extend(inputs);
+
+            let len = map.len();
+            let mut popped = 0;
+            while map.pop().is_some() {
+                map.invariants();
+                popped += 1;
+            }
+            popped == len && map.is_empty()
+        }
+    }
+
+    quickcheck! {
+        fn leaked_drain_keeps_invariants(inputs: Vec<(usize, usize)>, taken: usize) -> bool {
+            let mut map: BiMap<usize, usize, Clustering, Clustering> =
+                BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+            map.extend(inputs);
+
+            let len = map.len();
+            let taken = taken % (len + 1);
+            let mut drain = map.drain();
+            drain.by_ref().take(taken).for_each(drop);
+            mem::forget(drain);
+
+            map.invariants();
+            map.len() == len - taken
+        }
+    }
+
+    quickcheck! {
+        fn contains_agrees_with_get(inputs: Vec<(usize, usize)>, probes: Vec<usize>) -> bool {
+            let mut map: BiMap<usize, usize, Clustering, Clustering> =
+                BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+            map.extend(inputs);
+
+            probes.iter().all(|probe| {
+                map.contains_left(probe) == map.get_left(probe).is_some()
+                    && map.contains_right(probe) == map.get_right(probe).is_some()
+            })
+        }
+    }
+
+    quickcheck! {
+        fn split_off_partitions(inputs: Vec<(usize, usize)>, modulus: usize) -> bool {
+            let modulus = modulus % 4 + 1;
+            let mut map: BiMap<usize, usize, Clustering, Clustering> =
+                BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+            map.extend(inputs);
+            let original = map.clone();
+
+            let split = map.split_off(|&left, _| left % modulus == 0);
+            map.invariants();
+            split.invariants();
+
+            let mut union = map.clone();
+            union.extend(split.iter().map(|(&left, &right)| (left, right)));
+            union == original
+                && map.len() + split.len() == original.len()
+                && map.is_disjoint(&split)
+                && map.iter().all(|(left, _)| left % modulus != 0)
+                && split.iter().all(|(left, _)| left % modulus == 0)
+        }
+    }
+
+    #[test]
+    fn get_left_or_insert_with() {
+        let mut map = BiMap::new();
+        map.insert(1, 'a');
+
+        // hit: the closure is never called
+        assert_eq!(
+            &'a',
+            map.get_left_or_insert_with(1, || panic!("called on a hit"))
+        );
+        assert_eq!(1, map.len());
+
+        // miss: the closure's value is inserted
+        assert_eq!(&'b', map.get_left_or_insert_with(2, || 'b'));
+        assert_eq!(Some(&2), map.get_right(&'b'));
+        assert_eq!(2, map.len());
+
+        // right collision: the pair that owned 'a' is evicted
+        assert_eq!(&'a', map.get_left_or_insert_with(3, || 'a'));
+        map.invariants();
+        assert_eq!(None, map.get_left(&1));
+        assert_eq!(Some(&3), map.get_right(&'a'));
+        assert_eq!(2, map.len());
+    }
+
+    quickcheck! {
+        fn clone_is_identical(inputs: Vec<(usize, char)>) -> bool {
+            let original: BiMap<_, _> = inputs.into_iter().collect();
+            let mut clone = original.clone();
+            clone.invariants();
+
+            if clone != original || !clone.iter().eq(original.iter()) {
+                return false;
+            }
+
+            let pairs: Vec<_> = original.iter().map(|(&a, &b)| (a, b)).collect();
+            clone.insert(usize::MAX, 'a');
+            clone.remove_left(&0);
+            original.iter().map(|(&a, &b)| (a, b)).eq(pairs.into_iter())
+        }
+    }
+
+    quickcheck! {
+        fn invert_swaps_sides(inputs: Vec<(usize, char)>) -> bool {
+            let original: BiMap<_, _> = inputs.into_iter().collect();
+            let inverted = original.clone().invert();
+            inverted.invariants();
+
+            inverted.len() == original.len()
+                && original
+                    .iter()
+                    .all(|(a, b)| inverted.get_left(b) == Some(a) && inverted.get_right(a) == Some(b))
+                && inverted.invert() == original
+        }
+    }
+
+    #[test]
+    fn invert_swaps_hashers() {
+        let mut map: BiMap<usize, char, Clustering, RandomState> = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(RandomState::new())
+            .finish();
+        for (a, b) in (0..100).zip('a'..) {
+            map.insert(a, b);
+        }
+
+        let mut inverted: BiMap<char, usize, RandomState, Clustering> = map.invert();
+        inverted.invariants();
+        assert_eq!(Some(&'c'), inverted.get_right(&2));
+        inverted.insert('!', 1000);
+        inverted.invariants();
+        assert_eq!(Some(&1000), inverted.get_left(&'!'));
+    }
+
+    #[test]
+    fn rehash_moves_buckets() {
+        let mut map: BiMap<usize, usize> = (0..200).map(|x| (x, x)).collect();
+        let positions = |map: &BiMap<usize, usize>| -> Vec<_> {
+            map.left_data
+                .iter()
+                .map(|bucket| bucket.data.as_ref().map(|(key, ..)| *key))
+                .collect()
+        };
+        let before = map.clone();
+        let before_positions = positions(&map);
+
+        map.rehash();
+        map.invariants();
+        assert_eq!(before, map);
+        assert_ne!(before_positions, positions(&map));
+    }
+
+    quickcheck! {
+        fn clustered_inserts(inputs: Vec<(usize, usize)>) -> bool {
+            let mut map: BiMap<usize, usize, Clustering, Clustering> = BiMapBuilder::new()
+                .left_hasher(Default::default())
+                .right_hasher(Default::default())
+                .finish();
+            let mut expected = BiMap::new();
+
+            for (a, b) in inputs {
+                map.insert(a, b);
+                expected.insert(a, b);
+            }
+
+            map.invariants();
+            map.len() == expected.len()
+                && expected
+                    .iter()
+                    .all(|(a, b)| map.get_left(a) == Some(b) && map.get_right(b) == Some(a))
+        }
+    }
+
+    #[test]
+    fn test_long_displacement_chain() {
+        let mut map: BiMap<usize, usize, Clustering, Clustering> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .capacity(4096)
+                .finish();
+
+        for i in 0..4096 {
+            map.insert(i, i);
+        }
+
+        map.invariants();
+        assert_eq!(4096, map.len());
+        assert!((0..4096).all(|i| map.get_left(&i) == Some(&i) && map.get_right(&i) == Some(&i)));
+    }
+
+    quickcheck! {
+        fn capacity_is_power_of_two(capacity: usize, inputs: Vec<(usize, char)>) -> bool {
+            let mut map = BiMap::with_capacity(capacity);
+            let mut sizes = vec![map.left_data.len()];
+
+            for (a, b) in inputs {
+                map.insert(a, b);
+                sizes.push(map.left_data.len());
+            }
+            map.reserve(capacity);
+            sizes.push(map.left_data.len());
+            map.shrink_to_fit();
+            sizes.push(map.left_data.len());
+
+            sizes.into_iter().all(|size| size == 0 || size.is_power_of_two())
+        }
+    }
+
+    fn churn_small_table<B: BitField>(inputs: Vec<(bool, u8, u8)>) -> bool {
+        let mut map: BiMap<u8, u8, Clustering, Clustering, B> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .capacity(1)
+                .bitfield::<B>()
+                .finish();
+        map.invariants();
+
+        let mut expected = BiMap::new();
+        for (insert, a, b) in inputs {
+            if insert {
+                map.insert(a, b);
+                expected.insert(a, b);
+            } else {
+                map.remove_left(&a);
+                expected.remove_left(&a);
+            }
+            map.invariants();
+        }
+
+        map.len() == expected.len() && expected.iter().all(|(a, b)| map.get_left(a) == Some(b))
+    }
+
+    quickcheck! {
+        fn wide_neighbourhood_small_table(inputs: Vec<(bool, u8, u8)>) -> bool {
+            churn_small_table::<u64>(inputs.clone())
+                && churn_small_table::<u128>(inputs.clone())
+                && churn_small_table::<WideBitField<4>>(inputs)
+        }
+    }
+
+    #[test]
+    fn total_collision_fills_one_neighbourhood() {
+        let mut map: BiMap<u32, u32, Constant, RandomState, u8> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .bitfield::<u8>()
+                .finish();
+        let capacity = map.left_data.len();
+        for i in 0..8 {
+            map.insert(i, i);
+            map.invariants();
+        }
+        assert_eq!(8, map.len());
+        assert_eq!(capacity, map.left_data.len());
+        assert!((0..8).all(|i| map.get_left(&i) == Some(&i)));
+
+        // replacing a pair, or removing one, makes room again
+        map.insert(3, 30);
+        map.remove_left(&7);
+        map.insert(8, 8);
+        map.invariants();
+        assert_eq!(8, map.len());
+    }
+
+    #[test]
+    fn total_collision_overflow_panics() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut map: BiMap<u32, u32, RandomState, Constant, u8> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .bitfield::<u8>()
+                .finish();
+        map.extend((0..8).map(|i| (i, i)));
+        let capacity = map.left_data.len();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| map.insert(8, 8)));
+        let message = result.unwrap_err();
+        assert_eq!(
+            Some(&"too many keys with the same hash to fit in one neighbourhood"),
+            message.downcast_ref::<&str>()
+        );
+
+        // the map is left as it was, without having grown
+        map.invariants();
+        assert_eq!(8, map.len());
+        assert_eq!(capacity, map.left_data.len());
+        assert_eq!(None, map.get_left(&8));
+    }
+
+    #[test]
+    fn total_collision_overflow_hands_pair_back() {
+        let mut map: BiMap<u32, u32, RandomState, Constant, u8> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .bitfield::<u8>()
+                .finish();
+        map.extend((0..8).map(|i| (i, i)));
+        let capacity = map.left_data.len();
+
+        assert_eq!(Err((8, 8)), map.try_insert(8, 8));
+        assert_eq!(Err((8, 8)), map.insert_bounded(8, 8));
+
+        // the map is left as it was, without having grown
+        map.invariants();
+        assert_eq!(8, map.len());
+        assert_eq!(capacity, map.left_data.len());
+        assert_eq!(None, map.get_right(&8));
+
+        // replacing a right key frees a bucket in the same neighbourhood, so that still works
+        assert_eq!(Ok((Some(3), None)), map.insert_bounded(3, 8));
+        map.invariants();
+        assert_eq!(Some(&8), map.get_left(&3));
+        assert_eq!(capacity, map.left_data.len());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn total_collision_fails_deserialize() {
+        use crate::Lenient;
+
+        type Colliding = BiMap<u32, u32, RandomState, Constant, u8>;
+        let pairs: Vec<_> = (0..9).map(|i| format!(r#""{}":{}"#, i, i)).collect();
+        let json = format!("{{{}}}", pairs.join(","));
+
+        let strict = serde_json::from_str::<Colliding>(&json).err().unwrap();
+        assert!(strict
+            .to_string()
+            .contains("too many keys with the same hash in bimap"));
+        let lenient = serde_json::from_str::<Lenient<Colliding>>(&json)
+            .err()
+            .unwrap();
+        assert!(lenient
+            .to_string()
+            .contains("too many keys with the same hash in bimap"));
+    }
+
+    #[test]
+    fn table_at_least_as_wide_as_neighbourhood() {
+        let map: BiMap<u8, u8, _, _, WideBitField<4>> =
+            BiMapBuilder::new().capacity(1).bitfield().finish();
+        assert_eq!(256, map.left_data.len());
+
+        let mut map: BiMap<u8, u8, _, _, u64> = BiMapBuilder::new().capacity(0).bitfield().finish();
+        assert_eq!(0, map.left_data.len());
+        map.insert(1, 1);
+        assert_eq!(64, map.left_data.len());
+        map.remove_left(&1);
+        map.shrink_to_fit();
+        map.insert(1, 1);
+        map.shrink_to_fit();
+        assert_eq!(64, map.left_data.len());
+    }
+
+    quickcheck! {
+        fn narrow_neighbourhood_small_table(inputs: Vec<(bool, u8, u8)>) -> bool {
+            churn_small_table::<u8>(inputs)
+        }
+    }
+
+    #[test]
+    fn narrow_neighbourhood_tiny_capacity() {
+        let mut map: BiMap<u8, u8, Clustering, Clustering, u8> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .capacity(4)
+                .bitfield::<u8>()
+                .finish();
+        assert!(map.left_data.len() >= u8::size());
+        assert!(map.capacity() >= 4);
+
+        // every key is hashed into the same few buckets, so the map has to keep growing
+        for i in 0..=255 {
+            map.insert(i, 255 - i);
+            map.invariants();
+        }
+        assert_eq!(256, map.len());
+        assert!((0..=255).all(|i| map.get_left(&i) == Some(&(255 - i))));
+    }
+
+    #[test]
+    fn defragment_does_not_spread_keys() {
+        let mut map: BiMap<usize, usize, Clustering, Clustering> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .capacity(1024)
+                .finish();
+        map.extend((0..900).map(|x| (x, x)));
+        map.retain_left(|&left| left % 7 != 0 && left % 5 != 2);
+        let expected = map.clone();
+        let before = map.probe_stats();
+
+        map.defragment();
+        map.invariants();
+        let after = map.probe_stats();
+        assert_eq!(expected, map);
+        assert!(after.left_mean <= before.left_mean);
+        assert!(after.right_mean <= before.right_mean);
+    }
+
+    quickcheck! {
+        fn rename_left_keeps_pairs(inputs: Vec<(u8, u8)>, renames: Vec<(u8, u8)>) -> bool {
+            let mut map: BiMap<u8, u8, Clustering, Clustering> =
+                BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+            map.extend(inputs);
+
+            renames.into_iter().all(|(old, new)| {
+                let right = map.get_left(&old).copied();
+                let conflict = map.contains_left(&new);
+                let len = map.len();
+
+                let renamed = map.rename_left(&old, new);
+                map.invariants();
+                renamed == (right.is_some() && !conflict)
+                    && map.len() == len
+                    && if renamed {
+                        map.get_left(&new) == right.as_ref() && !map.contains_left(&old)
+                    } else {
+                        map.get_left(&old) == right.as_ref()
+                    }
+            })
+        }
+    }
+
+    #[test]
+    fn rename_left_cases() {
+        let mut map: BiMap<u32, char> = (0..10).zip('a'..).collect();
+
+        assert!(map.rename_left(&3, 30));
+        map.invariants();
+        assert_eq!(Some(&'d'), map.get_left(&30));
+        assert_eq!(Some(&30), map.get_right(&'d'));
+        assert_eq!(None, map.get_left(&3));
+
+        // the old key is absent
+        assert!(!map.rename_left(&3, 31));
+        assert_eq!(None, map.get_left(&31));
+
+        // the new key is already paired with something else
+        assert!(!map.rename_left(&4, 5));
+        map.invariants();
+        assert_eq!(Some(&'e'), map.get_left(&4));
+        assert_eq!(Some(&'f'), map.get_left(&5));
+        assert_eq!(10, map.len());
+    }
+
+    quickcheck! {
+        fn replace_right_of_matches_insert(inputs: Vec<(u8, u8)>, replacements: Vec<(u8, u8)>) -> bool {
+            let mut map: BiMap<u8, u8, Clustering, Clustering, u8> =
+                BiMapBuilder::with_hashers(Default::default(), Default::default())
+                    .bitfield::<u8>()
+                    .finish();
+            map.extend(inputs);
+            let mut expected: BiMap<u8, u8> = map.iter().collect();
+
+            replacements.into_iter().all(|(left, right)| {
+                let old = expected.get_left(&left).copied();
+                if old.is_some() {
+                    expected.insert(left, right);
+                }
+
+                let replaced = map.replace_right_of(&left, right);
+                map.invariants();
+                replaced == old && map.iter().collect::<BiMap<_, _>>() == expected
+            })
+        }
+    }
+
+    #[test]
+    fn replace_right_of_cases() {
+        let mut map: BiMap<u32, char> = (0..10).zip('a'..).collect();
+
+        // present, with a right key that isn't in the map
+        assert_eq!(Some('a'), map.replace_right_of(&0, 'z'));
+        map.invariants();
+        assert_eq!(Some(&'z'), map.get_left(&0));
+        assert_eq!(Some(&0), map.get_right(&'z'));
+        assert_eq!(None, map.get_right(&'a'));
+
+        // present, with the right key it is already paired with
+        assert_eq!(Some('z'), map.replace_right_of(&0, 'z'));
+        map.invariants();
+        assert_eq!(Some(&0), map.get_right(&'z'));
+
+        // present, with a right key that is paired with something else
+        assert_eq!(Some('b'), map.replace_right_of(&1, 'c'));
+        map.invariants();
+        assert_eq!(Some(&1), map.get_right(&'c'));
+        assert_eq!(None, map.get_left(&2));
+        assert_eq!(9, map.len());
+
+        // absent, so nothing is inserted
+        assert_eq!(None, map.replace_right_of(&20, 'y'));
+        assert_eq!(None, map.get_right(&'y'));
+        assert_eq!(9, map.len());
+    }
+
+    #[test]
+    fn modify_right_relocates() {
+        let mut map: BiMap<usize, usize, Identity, Identity> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+        map.insert(1, 3);
+        map.insert(2, 4);
+        assert!(map.right_data[3].data.is_some());
+
+        assert!(map.modify_right(&1, |right| *right = 10));
+        map.invariants();
+        assert!(map.right_data[3].data.is_none());
+        assert_eq!(
+            Some(10),
+            map.right_data[10].data.as_ref().map(|&(right, ..)| right)
+        );
+        assert_eq!(Some(&10), map.get_left(&1));
+        assert_eq!(Some(&1), map.get_right(&10));
+        assert_eq!(None, map.get_right(&3));
+        assert_eq!(2, map.len());
+
+        // changing the key to itself leaves it where it is
+        assert!(map.modify_right(&1, |_| ()));
+        map.invariants();
+        assert_eq!(Some(&1), map.get_right(&10));
+    }
+
+    #[test]
+    fn modify_right_rejects_collision() {
+        let mut map: BiMap<usize, usize, Identity, Identity> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+        map.insert(1, 3);
+        map.insert(2, 4);
+        let expected = map.clone();
+
+        assert!(!map.modify_right(&1, |right| *right = 4));
+        map.invariants();
+        assert_eq!(expected, map);
+        assert_eq!(Some(&1), map.get_right(&3));
+        assert_eq!(Some(&2), map.get_right(&4));
+
+        assert!(!map.modify_right(&5, |right| *right = 5));
+        assert_eq!(expected, map);
+    }
+
+    quickcheck! {
+        fn iter_mut_right_matches_model(inputs: Vec<(u8, u8)>, shift: u8) -> bool {
+            let mut map: BiMap<u8, u16> =
+                inputs.into_iter().map(|(a, b)| (a, u16::from(b))).collect();
+            let mut expected = map.clone();
+
+            let mut visited = 0;
+            let mut slots = map.iter_mut_right();
+            while let Some(slot) = slots.next() {
+                visited += 1;
+                let (left, right) = (*slot.left(), *slot.right());
+                if left % 2 == 0 {
+                    let new_right = right + u16::from(shift);
+                    let taken = expected.get_right(&new_right).is_some_and(|&l| l != left);
+                    match slot.set(new_right) {
+                        Ok(old) if old == right && !taken => {
+                            expected.replace_right_of(&left, new_right);
+                        }
+                        Err(rejected) if rejected == new_right && taken => (),
+                        _ => return false,
+                    }
+                }
+            }
+
+            map.invariants();
+            visited == map.len() && map == expected
+        }
+    }
+
+    #[test]
+    fn iter_mut_right_relocates_and_rejects() {
+        let mut map: BiMap<usize, usize, Identity, Identity> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default()).finish();
+        map.insert(1, 3);
+        map.insert(2, 4);
+
+        let mut slots = map.iter_mut_right();
+        while let Some(slot) = slots.next() {
+            match *slot.left() {
+                1 => assert_eq!(Ok(3), slot.set(10)),
+                2 => assert_eq!(Err(10), slot.set(10)),
+                _ => unreachable!(),
+            }
+        }
+
+        map.invariants();
+        assert!(map.right_data[3].data.is_none());
+        assert_eq!(Some(&1), map.get_right(&10));
+        assert_eq!(Some(&2), map.get_right(&4));
+    }
+
+    #[test]
+    fn iter_mut_right_into_full_neighbourhood() {
+        let mut map: BiMap<usize, usize, Identity, Identity, u8> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .bitfield::<u8>()
+                .finish();
+        let len = map.left_data.len();
+        for i in 0..8 {
+            map.insert(i, i * len);
+        }
+        map.insert(8, len - 1);
+        let expected = map.clone();
+
+        // there is no room for the new key without resizing, which would reorder the pairs
+        let mut slots = map.iter_mut_right();
+        while let Some(slot) = slots.next() {
+            if *slot.left() == 8 {
+                assert_eq!(Err(8 * len), slot.set(8 * len));
+            }
+        }
+
+        map.invariants();
+        assert_eq!(len, map.left_data.len());
+        assert_eq!(expected, map);
+    }
+
+    #[test]
+    fn replace_right_of_into_full_neighbourhood() {
+        // every right key lands in the same neighbourhood, so the new key has nowhere to go until
+        // the map is resized
+        let mut map: BiMap<usize, usize, Identity, Identity, u8> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .bitfield::<u8>()
+                .finish();
+        let len = map.left_data.len();
+        for i in 0..8 {
+            map.insert(i, i * len);
+        }
+        map.insert(8, len - 1);
+
+        assert_eq!(Some(len - 1), map.replace_right_of(&8, 8 * len));
+        map.invariants();
+        assert_eq!(Some(&(8 * len)), map.get_left(&8));
+        assert_eq!(Some(&8), map.get_right(&(8 * len)));
+        assert_eq!(9, map.len());
+    }
+
+    #[test]
+    fn rename_left_into_full_neighbourhood() {
+        // every key lands in the same neighbourhood, so the new key has nowhere to go until the
+        // map is resized
+        let mut map: BiMap<usize, usize, Identity, Identity, u8> =
+            BiMapBuilder::with_hashers(Default::default(), Default::default())
+                .bitfield::<u8>()
+                .finish();
+        let len = map.left_data.len();
+        for i in 0..8 {
+            map.insert(i * len, i);
+        }
+        map.insert(len - 1, 8);
+
+        assert!(map.rename_left(&(len - 1), 8 * len));
+        map.invariants();
+        assert_eq!(Some(&8), map.get_left(&(8 * len)));
+        assert_eq!(Some(&(8 * len)), map.get_right(&8));
+        assert_eq!(9, map.len());
+    }
 }
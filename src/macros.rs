@@ -0,0 +1,28 @@
+/// Creates a `BiMap` containing the given pairs. Pairs are inserted in order, so just like
+/// `insert`, if a left or right key appears more than once then the last pair containing it wins.
+///
+/// ```
+/// # #[macro_use] extern crate isomorphism;
+/// # fn main() {
+/// let map = bimap! {
+///     "Hello" => 5,
+///     "World" => 7,
+/// };
+///
+/// assert_eq!(Some(&5), map.get_left("Hello"));
+/// assert_eq!(Some(&"World"), map.get_right(&7));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! bimap {
+    (@unit $item:tt) => { () };
+    (@count $($item:tt)*) => { <[()]>::len(&[$($crate::bimap!(@unit $item)),*]) };
+    ($($left:expr => $right:expr),* $(,)?) => {{
+        let mut map = $crate::BiMap::new();
+        map.reserve($crate::bimap!(@count $($left)*));
+        $(
+            map.insert($left, $right);
+        )*
+        map
+    }};
+}
@@ -0,0 +1,120 @@
+//! Definitions of the index types used to pair buckets together.
+
+use core::convert::TryFrom;
+
+mod private {
+    /// Stops `PairIndex` from being implemented outside of this crate.
+    pub trait Sealed {}
+
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for usize {}
+}
+
+/// An unsigned integer type used to store positions within a `BiMap`. Every bucket stores two of
+/// these: the position of the key it is paired with on the other side of the map, and its own
+/// ideal position. See the `pair_index` method of `BiMapBuilder` for more information. This trait
+/// is sealed, so only `u16`, `u32` and `usize` can be used.
+pub trait PairIndex: private::Sealed + Copy + Eq {
+    /// Should return the largest position that can be stored in this type. A map can never have
+    /// more buckets than one more than this.
+    fn max() -> usize;
+
+    /// Should convert a position into this type. Panics if the position is larger than `max`.
+    fn from_usize(index: usize) -> Self;
+
+    /// Should convert a position stored in this type back into a `usize`.
+    fn to_usize(self) -> usize;
+}
+
+impl PairIndex for u16 {
+    fn max() -> usize {
+        u16::MAX as usize
+    }
+
+    fn from_usize(index: usize) -> Self {
+        u16::try_from(index).expect("pair index out of range")
+    }
+
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+}
+
+impl PairIndex for u32 {
+    fn max() -> usize {
+        u32::MAX as usize
+    }
+
+    fn from_usize(index: usize) -> Self {
+        u32::try_from(index).expect("pair index out of range")
+    }
+
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+}
+
+impl PairIndex for usize {
+    fn max() -> usize {
+        usize::MAX
+    }
+
+    fn from_usize(index: usize) -> Self {
+        index
+    }
+
+    fn to_usize(self) -> usize {
+        self
+    }
+}
+
+/// The default pair index type.
+pub type DefaultPairIndex = usize;
+
+/// Returns true if a map with the given number of buckets can store its positions in `I`.
+pub(crate) fn fits<I: PairIndex>(buckets: usize) -> bool {
+    buckets == 0 || buckets - 1 <= I::max()
+}
+
+/// Returns the largest number of buckets, always a power of two, that a map can have while storing
+/// its positions in `I`.
+pub(crate) fn max_buckets<I: PairIndex>() -> usize {
+    let bits = usize::BITS - I::max().leading_zeros();
+    match 1usize.checked_shl(bits) {
+        Some(buckets) if fits::<I>(buckets) => buckets,
+        _ => 1 << (bits - 1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fits, max_buckets, PairIndex};
+
+    #[test]
+    fn round_trip() {
+        for &index in &[0, 1, 1000, u16::MAX as usize] {
+            assert_eq!(index, u16::from_usize(index).to_usize());
+            assert_eq!(index, u32::from_usize(index).to_usize());
+            assert_eq!(index, usize::from_usize(index).to_usize());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "pair index out of range")]
+    fn from_usize_out_of_range() {
+        u16::from_usize(1 << 16);
+    }
+
+    #[test]
+    fn bucket_limits() {
+        assert!(fits::<u16>(0));
+        assert!(fits::<u16>(1 << 16));
+        assert!(!fits::<u16>((1 << 16) + 1));
+        assert!(fits::<u32>(1 << 20));
+        assert!(fits::<usize>(usize::MAX));
+
+        assert_eq!(1 << 16, max_buckets::<u16>());
+        assert_eq!(1 << (usize::BITS - 1), max_buckets::<usize>());
+    }
+}
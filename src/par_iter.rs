@@ -0,0 +1,148 @@
+use crate::{
+    bitfield::BitField,
+    bucket::Bucket,
+    pair_index::{DefaultPairIndex, PairIndex},
+    BiMap, BiMapBuilder,
+};
+
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::iter::{
+    IntoParallelIterator, IntoParallelRefIterator, ParallelExtend, ParallelIterator,
+};
+
+/// A parallel iterator over the pairs stored in a BiMap. The left buckets are split into ranges
+/// across threads, and each left key's pair is then looked up in the shared right buckets.
+pub struct ParIter<'a, L, R, B, I = DefaultPairIndex>
+where
+    L: 'a,
+    R: 'a,
+    B: 'a,
+{
+    left_data: &'a [Bucket<L, I, B>],
+    right_data: &'a [Bucket<R, I, B>],
+}
+
+impl<'a, L, R, B, I> Clone for ParIter<'a, L, R, B, I> {
+    fn clone(&self) -> Self {
+        ParIter {
+            left_data: self.left_data,
+            right_data: self.right_data,
+        }
+    }
+}
+
+impl<'a, L, R, B, I> ParallelIterator for ParIter<'a, L, R, B, I>
+where
+    L: Sync + 'a,
+    R: Sync + 'a,
+    B: Sync + 'a,
+    I: PairIndex + Sync + 'a,
+{
+    type Item = (&'a L, &'a R);
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        let ParIter {
+            left_data,
+            right_data,
+        } = self;
+        left_data
+            .par_iter()
+            .filter_map(move |bucket| {
+                bucket.data.as_ref().map(|(key, value, ..)| {
+                    (key, &right_data[value.to_usize()].data.as_ref().unwrap().0)
+                })
+            })
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<'a, L, R, LH, RH, B, I> IntoParallelIterator for &'a BiMap<L, R, LH, RH, B, I>
+where
+    L: Sync + 'a,
+    R: Sync + 'a,
+    B: Sync + 'a,
+    I: PairIndex + Sync + 'a,
+{
+    type Item = (&'a L, &'a R);
+    type Iter = ParIter<'a, L, R, B, I>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        let BiMap {
+            left_data,
+            right_data,
+            ..
+        } = self;
+        ParIter {
+            left_data,
+            right_data,
+        }
+    }
+}
+
+impl<L, R, LH, RH, B, I> BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq + Send,
+    R: Hash + Eq + Send,
+    LH: BuildHasher + Default + Sync,
+    RH: BuildHasher + Default + Sync,
+    B: BitField,
+    I: PairIndex,
+{
+    /// Creates a `BiMap` from a parallel iterator of pairs. See the `ParallelExtend`
+    /// implementation for how the work is split up. Just like `FromIterator`, if a left or right
+    /// key appears more than once then the last pair containing it wins, but parallel iterators
+    /// that don't have a fixed order (such as those made with `par_bridge`) may produce the pairs
+    /// in any order. The result is only guaranteed to be the same every time if no two pairs share
+    /// a left or right key.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let map: BiMap<u32, u32> = BiMap::par_build((0..1000).into_par_iter().map(|x| (x, x * 2)));
+    /// assert_eq!(1000, map.len());
+    /// assert_eq!(Some(&500), map.get_right(&1000));
+    /// ```
+    pub fn par_build<T: IntoParallelIterator<Item = (L, R)>>(iter: T) -> Self {
+        let mut output = BiMapBuilder::with_hashers(Default::default(), Default::default())
+            .bitfield::<B>()
+            .pair_index::<I>()
+            .finish();
+        output.par_extend(iter);
+        output
+    }
+}
+
+/// Hashes the incoming pairs in parallel, and then inserts them one at a time in the order that
+/// the parallel iterator produces them, as if by `insert`. Hashing is often the most expensive
+/// part of an insert, but the inserts themselves all write to the same buckets so they can't be
+/// split up between threads.
+impl<L, R, LH, RH, B, I> ParallelExtend<(L, R)> for BiMap<L, R, LH, RH, B, I>
+where
+    L: Hash + Eq + Send,
+    R: Hash + Eq + Send,
+    LH: BuildHasher + Sync,
+    RH: BuildHasher + Sync,
+    B: BitField,
+    I: PairIndex,
+{
+    fn par_extend<T: IntoParallelIterator<Item = (L, R)>>(&mut self, par_iter: T) {
+        let left_hasher = &self.left_hasher;
+        let right_hasher = &self.right_hasher;
+        let pairs: Vec<_> = par_iter
+            .into_par_iter()
+            .map(|(left, right)| {
+                let left_hash = Self::find_hash(&left, left_hasher);
+                let right_hash = Self::find_hash(&right, right_hasher);
+                (left, left_hash, right, right_hash)
+            })
+            .collect();
+
+        self.reserve(pairs.len());
+        for (left, left_hash, right, right_hash) in pairs {
+            self.insert_hashed(left, left_hash, right, right_hash);
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use isomorphism::{BiMap, BiMapBuilder};
+use isomorphism::bitfield::WideBitField;
+use isomorphism::{bimap, BiMap, BiMapBuilder, Keep, TryReserveError};
 use quickcheck::{quickcheck, TestResult};
+use std::collections::hash_map::RandomState;
 use std::collections::HashSet;
+use std::iter::FusedIterator;
+use std::mem;
 
 quickcheck! {
     fn test_capacity(cap: usize) -> bool {
@@ -8,10 +12,255 @@ quickcheck! {
     }
 }
 
+quickcheck! {
+    fn test_with_capacity(cap: usize) -> bool {
+        BiMap::<(), ()>::with_capacity(cap).capacity() >= cap
+    }
+}
+
+quickcheck! {
+    fn raw_capacity_and_capacity(cap: u16, factor: u8) -> bool {
+        let load_factor = (f32::from(factor) + 1.0) / 256.0;
+        let map: BiMap<(), ()> = BiMapBuilder::new()
+            .capacity(cap as usize)
+            .load_factor(load_factor)
+            .finish();
+        let raw = map.raw_capacity();
+
+        (raw == 0 || raw.is_power_of_two())
+            && map.capacity() == (raw as f32 * load_factor) as usize
+            && map.capacity() <= raw
+            && map.capacity() >= cap as usize
+    }
+}
+
+quickcheck! {
+    fn raw_buckets_reconstruct_pairs(pairs: Vec<(u16, u16)>) -> bool {
+        let map: BiMap<_, _> = pairs.into_iter().collect();
+        let left = map.left_buckets();
+        let right = map.right_buckets();
+
+        let from_buckets: HashSet<_> = left
+            .iter()
+            .filter_map(|bucket| {
+                let key = bucket.key()?;
+                let pair = &right[bucket.pair_index()?];
+                Some((key, pair.key()?))
+            })
+            .collect();
+        let from_iter: HashSet<_> = map.iter().collect();
+
+        left.len() == map.raw_capacity()
+            && right.iter().filter(|bucket| bucket.key().is_some()).count() == map.len()
+            && from_buckets == from_iter
+    }
+}
+
+quickcheck! {
+    fn cloned_hashers_match_layout(pairs: Vec<(u16, u16)>) -> bool {
+        let mut original: BiMap<u16, u16> = BiMap::with_capacity(64);
+        let mut copy = BiMapBuilder::new()
+            .capacity(64)
+            .clone_hashers_from(&original)
+            .finish();
+        for &(left, right) in &pairs {
+            original.insert(left, right);
+            copy.insert(left, right);
+        }
+
+        let layout = |map: &BiMap<u16, u16>| {
+            let left: Vec<_> = map
+                .left_buckets()
+                .iter()
+                .map(|bucket| (bucket.key().cloned(), bucket.pair_index(), bucket.hash()))
+                .collect();
+            let right: Vec<_> = map
+                .right_buckets()
+                .iter()
+                .map(|bucket| (bucket.key().cloned(), bucket.pair_index(), bucket.hash()))
+                .collect();
+            (left, right)
+        };
+        layout(&original) == layout(&copy)
+    }
+}
+
+quickcheck! {
+    fn ord_ignores_insertion_order(inputs: Vec<(u8, u8)>) -> bool {
+        let forwards: BiMap<_, _> = inputs.iter().cloned().collect();
+        let mut backwards: BiMap<_, _> = BiMap::with_capacity(1024);
+        // insert the final pairs in the opposite order, so the buckets are laid out differently
+        let mut pairs: Vec<_> = forwards.iter().map(|(&a, &b)| (a, b)).collect();
+        pairs.reverse();
+        backwards.extend(pairs);
+
+        forwards.cmp(&backwards) == std::cmp::Ordering::Equal
+            && forwards.partial_cmp(&backwards) == Some(std::cmp::Ordering::Equal)
+    }
+}
+
+quickcheck! {
+    fn ord_is_total_order(a: Vec<(u8, u8)>, b: Vec<(u8, u8)>, c: Vec<(u8, u8)>) -> bool {
+        let a: BiMap<_, _> = a.into_iter().collect();
+        let b: BiMap<_, _> = b.into_iter().collect();
+        let c: BiMap<_, _> = c.into_iter().collect();
+
+        let consistent_with_eq = (a.cmp(&b) == std::cmp::Ordering::Equal) == (a == b);
+        let antisymmetric = a.cmp(&b) == b.cmp(&a).reverse();
+        let transitive = !(a <= b && b <= c) || a <= c;
+        consistent_with_eq && antisymmetric && transitive
+    }
+}
+
+#[test]
+fn ord_sorts_maps() {
+    let maps = vec![
+        bimap! { 2 => 'a' },
+        bimap! { 1 => 'b' },
+        bimap! {},
+        bimap! { 1 => 'a', 2 => 'b' },
+        bimap! { 1 => 'a' },
+    ];
+    let set: std::collections::BTreeSet<_> = maps.into_iter().collect();
+
+    let expected = vec![
+        bimap! {},
+        bimap! { 1 => 'a' },
+        bimap! { 1 => 'a', 2 => 'b' },
+        bimap! { 1 => 'b' },
+        bimap! { 2 => 'a' },
+    ];
+    assert_eq!(expected, set.into_iter().collect::<Vec<_>>());
+}
+
+quickcheck! {
+    fn handles_resolve_to_their_pair(inputs: Vec<(u16, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        map.iter().all(|(left, right)| {
+            let handle = map.left_handle(left);
+            handle.is_some()
+                && handle == map.right_handle(right)
+                && map.resolve(handle.unwrap()) == (left, right)
+        })
+    }
+}
+
+#[test]
+#[should_panic(expected = "handle does not refer to a pair")]
+fn handle_invalidated_by_removal() {
+    let mut map = bimap! { 1 => 'a' };
+    let handle = map.left_handle(&1).unwrap();
+    assert_eq!((&1, &'a'), map.resolve(handle));
+
+    // the pair's bucket is now empty, so the handle no longer refers to anything
+    map.remove_left(&1);
+    map.resolve(handle);
+}
+
+quickcheck! {
+    fn capacity_for_matches_builder(pairs: u16) -> bool {
+        let pairs = pairs as usize;
+        let map: BiMap<u32, char> = BiMapBuilder::new().capacity(pairs).finish();
+        type Narrow = BiMap<u32, char, RandomState, RandomState, u8>;
+        let narrow: Narrow = BiMapBuilder::new().capacity(pairs).bitfield::<u8>().finish();
+
+        map.raw_capacity() == BiMap::<u32, char>::capacity_for(pairs)
+            && narrow.raw_capacity() == Narrow::capacity_for(pairs)
+            && BiMap::<u32, char>::with_capacity(pairs).raw_capacity()
+                == BiMap::<u32, char>::capacity_for(pairs)
+    }
+}
+
+#[test]
+fn capacity_for_stops_at_pair_index_limit() {
+    type Small = BiMap<u32, char, RandomState, RandomState, u32, u16>;
+    for &pairs in &[100, 50_000, 100_000, 1 << 20] {
+        let map: Small = BiMapBuilder::new()
+            .pair_index::<u16>()
+            .capacity(pairs)
+            .finish();
+        assert_eq!(Small::capacity_for(pairs), map.raw_capacity());
+    }
+    assert_eq!(1 << 16, Small::capacity_for(100_000));
+}
+
+#[test]
+fn bimap_macro_many_entries() {
+    // enough pairs to hit the recursion limit if the macro counted them one at a time
+    let map = bimap! {
+        0 => 1000, 1 => 1001, 2 => 1002, 3 => 1003, 4 => 1004, 5 => 1005,
+        6 => 1006, 7 => 1007, 8 => 1008, 9 => 1009, 10 => 1010, 11 => 1011,
+        12 => 1012, 13 => 1013, 14 => 1014, 15 => 1015, 16 => 1016, 17 => 1017,
+        18 => 1018, 19 => 1019, 20 => 1020, 21 => 1021, 22 => 1022, 23 => 1023,
+        24 => 1024, 25 => 1025, 26 => 1026, 27 => 1027, 28 => 1028, 29 => 1029,
+        30 => 1030, 31 => 1031, 32 => 1032, 33 => 1033, 34 => 1034, 35 => 1035,
+        36 => 1036, 37 => 1037, 38 => 1038, 39 => 1039, 40 => 1040, 41 => 1041,
+        42 => 1042, 43 => 1043, 44 => 1044, 45 => 1045, 46 => 1046, 47 => 1047,
+        48 => 1048, 49 => 1049, 50 => 1050, 51 => 1051, 52 => 1052, 53 => 1053,
+        54 => 1054, 55 => 1055, 56 => 1056, 57 => 1057, 58 => 1058, 59 => 1059,
+        60 => 1060, 61 => 1061, 62 => 1062, 63 => 1063, 64 => 1064, 65 => 1065,
+        66 => 1066, 67 => 1067, 68 => 1068, 69 => 1069, 70 => 1070, 71 => 1071,
+        72 => 1072, 73 => 1073, 74 => 1074, 75 => 1075, 76 => 1076, 77 => 1077,
+        78 => 1078, 79 => 1079, 80 => 1080, 81 => 1081, 82 => 1082, 83 => 1083,
+        84 => 1084, 85 => 1085, 86 => 1086, 87 => 1087, 88 => 1088, 89 => 1089,
+        90 => 1090, 91 => 1091, 92 => 1092, 93 => 1093, 94 => 1094, 95 => 1095,
+        96 => 1096, 97 => 1097, 98 => 1098, 99 => 1099, 100 => 1100, 101 => 1101,
+        102 => 1102, 103 => 1103, 104 => 1104, 105 => 1105, 106 => 1106, 107 => 1107,
+        108 => 1108, 109 => 1109, 110 => 1110, 111 => 1111, 112 => 1112, 113 => 1113,
+        114 => 1114, 115 => 1115, 116 => 1116, 117 => 1117, 118 => 1118, 119 => 1119,
+        120 => 1120, 121 => 1121, 122 => 1122, 123 => 1123, 124 => 1124, 125 => 1125,
+        126 => 1126, 127 => 1127, 128 => 1128, 129 => 1129, 130 => 1130, 131 => 1131,
+        132 => 1132, 133 => 1133, 134 => 1134, 135 => 1135, 136 => 1136, 137 => 1137,
+        138 => 1138, 139 => 1139, 140 => 1140, 141 => 1141, 142 => 1142, 143 => 1143,
+        144 => 1144, 145 => 1145, 146 => 1146, 147 => 1147, 148 => 1148, 149 => 1149,
+        150 => 1150, 151 => 1151, 152 => 1152, 153 => 1153, 154 => 1154, 155 => 1155,
+        156 => 1156, 157 => 1157, 158 => 1158, 159 => 1159, 160 => 1160, 161 => 1161,
+        162 => 1162, 163 => 1163, 164 => 1164, 165 => 1165, 166 => 1166, 167 => 1167,
+        168 => 1168, 169 => 1169, 170 => 1170, 171 => 1171, 172 => 1172, 173 => 1173,
+        174 => 1174, 175 => 1175, 176 => 1176, 177 => 1177, 178 => 1178, 179 => 1179,
+        180 => 1180, 181 => 1181, 182 => 1182, 183 => 1183, 184 => 1184, 185 => 1185,
+        186 => 1186, 187 => 1187, 188 => 1188, 189 => 1189, 190 => 1190, 191 => 1191,
+        192 => 1192, 193 => 1193, 194 => 1194, 195 => 1195, 196 => 1196, 197 => 1197,
+        198 => 1198, 199 => 1199, 200 => 1200, 201 => 1201, 202 => 1202, 203 => 1203,
+        204 => 1204, 205 => 1205, 206 => 1206, 207 => 1207, 208 => 1208, 209 => 1209,
+        210 => 1210, 211 => 1211, 212 => 1212, 213 => 1213, 214 => 1214, 215 => 1215,
+        216 => 1216, 217 => 1217, 218 => 1218, 219 => 1219, 220 => 1220, 221 => 1221,
+        222 => 1222, 223 => 1223, 224 => 1224, 225 => 1225, 226 => 1226, 227 => 1227,
+        228 => 1228, 229 => 1229, 230 => 1230, 231 => 1231, 232 => 1232, 233 => 1233,
+        234 => 1234, 235 => 1235, 236 => 1236, 237 => 1237, 238 => 1238, 239 => 1239,
+        240 => 1240, 241 => 1241, 242 => 1242, 243 => 1243, 244 => 1244, 245 => 1245,
+        246 => 1246, 247 => 1247, 248 => 1248, 249 => 1249, 250 => 1250, 251 => 1251,
+        252 => 1252, 253 => 1253, 254 => 1254, 255 => 1255, 256 => 1256, 257 => 1257,
+        258 => 1258, 259 => 1259, 260 => 1260, 261 => 1261, 262 => 1262, 263 => 1263,
+        264 => 1264, 265 => 1265, 266 => 1266, 267 => 1267, 268 => 1268, 269 => 1269,
+        270 => 1270, 271 => 1271, 272 => 1272, 273 => 1273, 274 => 1274, 275 => 1275,
+        276 => 1276, 277 => 1277, 278 => 1278, 279 => 1279, 280 => 1280, 281 => 1281,
+        282 => 1282, 283 => 1283, 284 => 1284, 285 => 1285, 286 => 1286, 287 => 1287,
+        288 => 1288, 289 => 1289, 290 => 1290, 291 => 1291, 292 => 1292, 293 => 1293,
+        294 => 1294, 295 => 1295, 296 => 1296, 297 => 1297, 298 => 1298, 299 => 1299,
+    };
+    assert_eq!(300, map.len());
+    assert!((0..300).all(|i| map.get_left(&i) == Some(&(i + 1000))));
+}
+
+#[test]
+fn with_hashers_infers_types() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    let mut map = BiMap::with_hashers(
+        BuildHasherDefault::<DefaultHasher>::default(),
+        BuildHasherDefault::<DefaultHasher>::default(),
+    );
+    map.insert(1, 'a');
+    assert_eq!(Some(&'a'), map.get_left(&1));
+    assert_eq!(Some(&1), map.get_right(&'a'));
+}
+
 quickcheck! {
     fn remove_from_empty(a: usize, b: char) -> bool {
         let mut map: BiMap<usize, char> = BiMap::new();
-        map.remove_left(&a) == None && map.remove_right(&b) == None
+        map.remove_left(&a).is_none() && map.remove_right(&b).is_none()
     }
 }
 
@@ -77,8 +326,8 @@ quickcheck! {
         inputs
             .into_iter()
             .all(|(a, b)| {
-                let old_b = map.get_left(&a).map(|&x| x);
-                let old_a = map.get_right(&b).map(|&x| x);
+                let old_b = map.get_left(&a).copied();
+                let old_a = map.get_right(&b).copied();
 
                 map.insert(a, b) == (old_b, old_a)
             })
@@ -124,3 +373,1704 @@ quickcheck! {
         refs == vals
     }
 }
+
+quickcheck! {
+    fn contains_matches_get(inputs: Vec<(usize, char)>, a: usize, b: char) -> bool {
+        let mut map = BiMap::new();
+
+        for (a, b) in inputs {
+            map.insert(a, b);
+        }
+
+        map.contains_left(&a) == map.get_left(&a).is_some()
+            && map.contains_right(&b) == map.get_right(&b).is_some()
+    }
+}
+
+quickcheck! {
+    fn clear_keeps_capacity(inputs: Vec<(usize, char)>) -> bool {
+        let mut map = BiMap::new();
+
+        for &(a, b) in &inputs {
+            map.insert(a, b);
+        }
+
+        let capacity = map.capacity();
+        map.clear();
+
+        if !map.is_empty() || map.iter().next().is_some() || map.capacity() != capacity {
+            return false;
+        }
+
+        let mut expected = BiMap::new();
+        for (a, b) in inputs {
+            map.insert(a, b);
+            expected.insert(a, b);
+        }
+
+        map.capacity() == capacity && map == expected
+    }
+}
+
+quickcheck! {
+    fn reserve_prevents_growth(inputs: Vec<(usize, char)>) -> bool {
+        let mut map = BiMap::new();
+        map.insert(0, '\0');
+
+        map.reserve(inputs.len());
+        let capacity = map.capacity();
+        if capacity < inputs.len() + 1 {
+            return false;
+        }
+
+        for (a, b) in inputs {
+            map.insert(a, b);
+        }
+
+        map.capacity() == capacity
+    }
+}
+
+#[test]
+fn shrink_to_fit_after_removal() {
+    let mut map = BiMap::new();
+    for i in 0..10_000 {
+        map.insert(i, i.to_string());
+    }
+
+    let capacity = map.capacity();
+    for i in 100..10_000 {
+        map.remove_left(&i);
+    }
+    map.shrink_to_fit();
+
+    assert_eq!(100, map.len());
+    assert!(map.capacity() < capacity);
+    assert!(map.capacity() >= 100);
+    for i in 0..100 {
+        assert_eq!(Some(&i.to_string()), map.get_left(&i));
+        assert_eq!(Some(&i), map.get_right(&i.to_string()));
+    }
+
+    // already minimal, so nothing should change
+    let capacity = map.capacity();
+    map.shrink_to_fit();
+    assert_eq!(capacity, map.capacity());
+}
+
+#[test]
+fn shrink_to_fit_empty() {
+    let mut map = BiMap::with_capacity(1024);
+    map.insert(1, 2);
+    map.remove_left(&1);
+
+    map.shrink_to_fit();
+    assert_eq!(0, map.capacity());
+
+    map.insert(3, 4);
+    assert_eq!(Some(&4), map.get_left(&3));
+}
+
+quickcheck! {
+    fn shrink_to_keeps_headroom(inputs: Vec<(usize, char)>, removed: usize, min_capacity: u16) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let left: Vec<_> = map.left_keys().copied().take(removed).collect();
+        for a in left {
+            map.remove_left(&a);
+        }
+        let expected = map.clone();
+        let capacity = map.capacity();
+
+        map.shrink_to(min_capacity as usize);
+        map.capacity() <= capacity
+            && map.capacity() >= std::cmp::min(capacity, min_capacity as usize)
+            && map.capacity() >= map.len()
+            && map == expected
+    }
+}
+
+quickcheck! {
+    fn insert_unchecked_matches_insert(inputs: HashSet<u16>) -> bool {
+        let mut expected = BiMap::new();
+        let mut map = BiMap::new();
+        for &i in &inputs {
+            expected.insert(i, u32::from(i) * 3);
+            map.insert_unchecked(i, u32::from(i) * 3);
+        }
+
+        map.len() == inputs.len() && map == expected
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "already in the map")]
+fn insert_unchecked_duplicate_panics_in_debug() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+    map.insert_unchecked(2, 'a');
+}
+
+#[test]
+fn shrink_to_lower_bound() {
+    let mut map = BiMap::with_capacity(10_000);
+    for i in 0..100 {
+        map.insert(i, i.to_string());
+    }
+    let capacity = map.capacity();
+
+    map.shrink_to(1000);
+    assert!(map.capacity() < capacity);
+    assert!(map.capacity() >= 1000);
+
+    // never shrinks below what the pairs already in the map need
+    map.shrink_to(0);
+    assert!(map.capacity() >= 100);
+    assert_eq!(100, map.len());
+    for i in 0..100 {
+        assert_eq!(Some(&i.to_string()), map.get_left(&i));
+    }
+
+    // asking for more than the current capacity does nothing
+    let capacity = map.capacity();
+    map.shrink_to(capacity * 4);
+    assert_eq!(capacity, map.capacity());
+}
+
+quickcheck! {
+    fn drain_all(inputs: Vec<(usize, char)>) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let capacity = map.capacity();
+
+        let mut expected: Vec<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+        let mut drained: Vec<_> = map.drain().collect();
+        expected.sort();
+        drained.sort();
+
+        drained == expected && map.is_empty() && map.capacity() == capacity
+    }
+}
+
+quickcheck! {
+    fn drain_partial(inputs: Vec<(usize, char)>, taken: usize) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let capacity = map.capacity();
+
+        map.drain().take(taken).for_each(drop);
+
+        if !map.is_empty() || map.iter().next().is_some() || map.capacity() != capacity {
+            return false;
+        }
+
+        map.insert(1, 'a');
+        map.len() == 1 && map.get_left(&1) == Some(&'a') && map.get_right(&'a') == Some(&1)
+    }
+}
+
+quickcheck! {
+    fn drain_leaked(inputs: Vec<(usize, char)>, taken: usize) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let len = map.len();
+        let taken = taken % (len + 1);
+
+        let mut drain = map.drain();
+        let mut yielded = Vec::new();
+        for remaining in (len - taken..len).rev() {
+            yielded.push(drain.next().unwrap());
+            if drain.len() != remaining {
+                return false;
+            }
+        }
+        mem::forget(drain);
+
+        if map.len() != len - taken || map.iter().count() != len - taken {
+            return false;
+        }
+        if yielded
+            .iter()
+            .any(|(l, r)| map.contains_left(l) || map.contains_right(r))
+        {
+            return false;
+        }
+
+        map.extend(yielded);
+        map.len() == len && map.iter().all(|(l, r)| map.get_left(l) == Some(r))
+    }
+}
+
+quickcheck! {
+    fn try_insert(inputs: Vec<(usize, char)>, a: usize, b: char) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let before = map.clone();
+        let exists = map.contains_left(&a) || map.contains_right(&b);
+
+        match map.try_insert(a, b) {
+            Ok(()) => {
+                !exists
+                    && map.len() == before.len() + 1
+                    && map.get_left(&a) == Some(&b)
+                    && map.get_right(&b) == Some(&a)
+            }
+            Err(pair) => exists && pair == (a, b) && map == before,
+        }
+    }
+}
+
+#[test]
+fn try_insert_collision() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+    map.insert(2, 'b');
+
+    assert_eq!(Err((1, 'c')), map.try_insert(1, 'c'));
+    assert_eq!(Err((3, 'b')), map.try_insert(3, 'b'));
+    assert_eq!(Err((1, 'b')), map.try_insert(1, 'b'));
+
+    assert_eq!(2, map.len());
+    assert_eq!(Some(&'a'), map.get_left(&1));
+    assert_eq!(Some(&'b'), map.get_left(&2));
+    assert_eq!(Some(&1), map.get_right(&'a'));
+    assert_eq!(Some(&2), map.get_right(&'b'));
+}
+
+quickcheck! {
+    fn insert_bounded_never_exceeds_limit(inputs: Vec<(u8, u8)>, limit: u8) -> bool {
+        let limit = limit as usize % 16;
+        let mut map: BiMap<u8, u8> = BiMapBuilder::new().max_len(limit).finish();
+        let mut model: BiMap<u8, u8> = BiMap::new();
+
+        inputs.into_iter().all(|(a, b)| {
+            let full = model.len() == limit;
+            let new = !model.contains_left(&a) && !model.contains_right(&b);
+            let ok = match map.insert_bounded(a, b) {
+                Ok(evicted) => !(full && new) && evicted == model.insert(a, b),
+                Err(pair) => full && new && pair == (a, b),
+            };
+            ok && map == model && map.len() <= limit
+        })
+    }
+}
+
+#[test]
+fn insert_bounded_at_limit() {
+    let mut map: BiMap<u32, char> = BiMapBuilder::new().max_len(2).finish();
+    assert_eq!(Ok((None, None)), map.insert_bounded(1, 'a'));
+    assert_eq!(Ok((None, None)), map.insert_bounded(2, 'b'));
+
+    // new pairs are rejected, by both bounded inserts
+    assert_eq!(Err((3, 'c')), map.insert_bounded(3, 'c'));
+    assert_eq!(Err((3, 'c')), map.try_insert(3, 'c'));
+    assert_eq!(2, map.len());
+
+    // replacing either key, or both at once, is allowed
+    assert_eq!(Ok((Some('a'), None)), map.insert_bounded(1, 'c'));
+    assert_eq!(Ok((None, Some(2))), map.insert_bounded(3, 'b'));
+    assert_eq!(Ok((Some('c'), Some(3))), map.insert_bounded(1, 'b'));
+    assert_eq!(1, map.len());
+
+    // which leaves room for a new pair again
+    assert_eq!(Ok((None, None)), map.insert_bounded(4, 'd'));
+    assert_eq!(bimap! { 1 => 'b', 4 => 'd' }, map);
+}
+
+#[test]
+fn extend_ignores_limit() {
+    let mut map: BiMap<u32, char> = BiMapBuilder::new().max_len(2).finish();
+    map.extend(vec![(1, 'a'), (2, 'b'), (3, 'c'), (1, 'd')]);
+    assert_eq!(bimap! { 1 => 'd', 2 => 'b', 3 => 'c' }, map);
+
+    // a plain insert always goes through too, but a bounded one is still rejected
+    assert_eq!((None, None), map.insert(4, 'e'));
+    assert_eq!(4, map.len());
+    assert_eq!(Err((5, 'f')), map.insert_bounded(5, 'f'));
+}
+
+#[test]
+fn merge_with_ignores_limit() {
+    let mut map: BiMap<u32, char> = BiMapBuilder::new().max_len(2).finish();
+    map.insert(1, 'a');
+    map.insert(2, 'b');
+
+    let other = bimap! { 1 => 'c', 3 => 'd' };
+    map.merge_with(other, |_, _, _, _| Keep::KeepOther);
+    assert_eq!(bimap! { 1 => 'c', 2 => 'b', 3 => 'd' }, map);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_extend_ignores_limit() {
+    use rayon::iter::ParallelExtend;
+
+    let mut map: BiMap<u32, char> = BiMapBuilder::new().max_len(2).finish();
+    map.insert(1, 'a');
+    map.par_extend(vec![(2, 'b'), (3, 'c'), (1, 'd')]);
+    assert_eq!(bimap! { 1 => 'd', 2 => 'b', 3 => 'c' }, map);
+}
+
+#[test]
+fn append_ignores_limit() {
+    let mut map: BiMap<u32, char> = BiMapBuilder::new().max_len(2).finish();
+    map.insert(1, 'a');
+
+    let mut other = bimap! { 1 => 'b', 2 => 'c', 3 => 'd' };
+    map.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(bimap! { 1 => 'b', 2 => 'c', 3 => 'd' }, map);
+}
+
+#[test]
+fn insert_all_ignores_limit() {
+    let mut map: BiMap<u32, char> = BiMapBuilder::new().max_len(2).finish();
+    map.insert(1, 'a');
+
+    // every pair goes in, and only the one that replaced a key is counted
+    assert_eq!(1, map.insert_all(vec![(2, 'b'), (3, 'c'), (2, 'd')]));
+    assert_eq!(bimap! { 1 => 'a', 2 => 'd', 3 => 'c' }, map);
+}
+
+#[test]
+fn index_by_left() {
+    let mut map = BiMap::new();
+    map.insert(String::from("Hello"), 5);
+
+    assert_eq!(5, map["Hello"]);
+}
+
+#[test]
+#[should_panic(expected = "no entry found for left key")]
+fn index_missing_left() {
+    let map: BiMap<String, usize> = BiMap::new();
+    let _ = map["Hello"];
+}
+
+#[test]
+fn from_array() {
+    let map = BiMap::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+    let expected: BiMap<_, _> = vec![(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+
+    assert_eq!(expected, map);
+}
+
+#[test]
+fn from_array_duplicate_right() {
+    let map = BiMap::from([(1, 'a'), (2, 'b'), (3, 'a')]);
+
+    assert_eq!(2, map.len());
+    assert_eq!(None, map.get_left(&1));
+    assert_eq!(Some(&'a'), map.get_left(&3));
+    assert_eq!(Some(&3), map.get_right(&'a'));
+    assert_eq!(Some(&2), map.get_right(&'b'));
+}
+
+#[test]
+fn bimap_macro() {
+    let map = bimap! {
+        1 => 'a',
+        2 => 'b',
+        3 => 'c',
+    };
+    let expected: BiMap<_, _> = vec![(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+
+    assert_eq!(expected, map);
+    assert_eq!(map, bimap! { 1 => 'a', 2 => 'b', 3 => 'c' });
+}
+
+#[test]
+fn bimap_macro_empty() {
+    let mut map: BiMap<usize, char> = bimap! {};
+    assert!(map.is_empty());
+
+    map.insert(1, 'a');
+    assert_eq!(Some(&'a'), map.get_left(&1));
+}
+
+quickcheck! {
+    fn iterate_reversed(inputs: Vec<(usize, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+
+        let forwards: Vec<_> = map.iter().collect();
+        let mut backwards: Vec<_> = map.iter().rev().collect();
+        backwards.reverse();
+
+        forwards == backwards
+    }
+}
+
+quickcheck! {
+    fn iterate_from_both_ends(inputs: Vec<(usize, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+
+        let mut iter = map.iter();
+        let mut pairs = Vec::new();
+        while let Some(pair) = iter.next() {
+            pairs.push(pair);
+            match iter.next_back() {
+                Some(pair) => pairs.push(pair),
+                None => break,
+            }
+        }
+
+        let mut expected: Vec<_> = map.iter().collect();
+        pairs.sort();
+        expected.sort();
+        pairs == expected
+    }
+}
+
+quickcheck! {
+    fn iter_exact_size(inputs: Vec<(usize, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+
+        let mut iter = map.iter();
+        for remaining in (0..=map.len()).rev() {
+            if iter.len() != remaining || iter.size_hint() != (remaining, Some(remaining)) {
+                return false;
+            }
+            iter.next();
+        }
+
+        iter.next().is_none()
+    }
+}
+
+quickcheck! {
+    fn into_iter_exact_size(inputs: Vec<(usize, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let len = map.len();
+
+        let mut iter = map.into_iter();
+        for remaining in (0..=len).rev() {
+            if iter.len() != remaining || iter.size_hint() != (remaining, Some(remaining)) {
+                return false;
+            }
+            iter.next();
+        }
+
+        iter.next().is_none()
+    }
+}
+
+fn exhaust_fused<I: FusedIterator>(mut iter: I) -> bool {
+    while iter.next().is_some() {}
+    (0..8).all(|_| iter.next().is_none())
+}
+
+quickcheck! {
+    fn iterators_are_fused(inputs: Vec<(usize, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+
+        exhaust_fused(map.iter()) && exhaust_fused(map.into_iter())
+    }
+}
+
+quickcheck! {
+    fn clone_partial_iter(inputs: Vec<(usize, char)>, skip: usize) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+
+        let mut iter = map.iter();
+        for _ in 0..skip {
+            iter.next();
+        }
+
+        let remaining: Vec<_> = iter.clone().collect();
+        remaining.len() == iter.len() && remaining == iter.collect::<Vec<_>>()
+    }
+}
+
+quickcheck! {
+    fn single_side_keys(inputs: Vec<(usize, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+
+        let mut expected_left: Vec<_> = map.iter().map(|(a, _)| a).collect();
+        let mut expected_right: Vec<_> = map.iter().map(|(_, b)| b).collect();
+        let mut left: Vec<_> = map.left_keys().collect();
+        let mut right: Vec<_> = map.right_keys().collect();
+
+        expected_left.sort();
+        expected_right.sort();
+        left.sort();
+        right.sort();
+
+        map.left_keys().len() == map.len()
+            && map.right_keys().len() == map.len()
+            && left == expected_left
+            && right == expected_right
+    }
+}
+
+quickcheck! {
+    fn into_single_side(inputs: Vec<(usize, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+
+        let mut expected_left: Vec<_> = map.left_keys().copied().collect();
+        let mut expected_right: Vec<_> = map.right_keys().copied().collect();
+        let mut left: Vec<_> = map.clone().into_left().collect();
+        let mut right: Vec<_> = map.clone().into_right().rev().collect();
+
+        expected_left.sort();
+        expected_right.sort();
+        left.sort();
+        right.sort();
+
+        map.clone().into_left().len() == map.len() && left == expected_left && right == expected_right
+    }
+}
+
+#[test]
+fn into_single_side_drops_other_side() {
+    use std::rc::Rc;
+
+    let tracker = Rc::new(());
+    let map: BiMap<_, _> = (0..100).map(|i| (i, (i, Rc::clone(&tracker)))).collect();
+    assert_eq!(101, Rc::strong_count(&tracker));
+
+    let mut lefts = map.into_left();
+    assert_eq!(Some(0), lefts.by_ref().min());
+    assert_eq!(1, Rc::strong_count(&tracker));
+
+    let map: BiMap<_, _> = (0..100).map(|i| ((i, Rc::clone(&tracker)), i)).collect();
+    let mut rights = map.into_right();
+    rights.next();
+    drop(rights);
+    assert_eq!(1, Rc::strong_count(&tracker));
+}
+
+/// A key that records its id in a shared log when it is dropped.
+struct DropLogged(u32, std::rc::Rc<std::cell::RefCell<Vec<u32>>>);
+
+impl PartialEq for DropLogged {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for DropLogged {}
+
+impl std::hash::Hash for DropLogged {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl Drop for DropLogged {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0);
+    }
+}
+
+quickcheck! {
+    fn into_iter_partial_drop_frees_each_key_once(len: u8, front: u8, back: u8) -> bool {
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let len = u32::from(len);
+        let map: BiMap<_, _> = (0..len)
+            .map(|i| {
+                (
+                    DropLogged(i, std::rc::Rc::clone(&log)),
+                    DropLogged(len + i, std::rc::Rc::clone(&log)),
+                )
+            })
+            .collect();
+
+        // the left keys are paired with right keys in arbitrary buckets, so stopping part way
+        // leaves right keys behind whose left keys are both taken and not yet visited
+        let mut iter = map.into_iter();
+        for _ in 0..front {
+            iter.next();
+        }
+        for _ in 0..back {
+            iter.next_back();
+        }
+        drop(iter);
+
+        let mut dropped = log.borrow().clone();
+        dropped.sort_unstable();
+        dropped == (0..2 * len).collect::<Vec<_>>()
+    }
+}
+
+quickcheck! {
+    fn keys_as_sets(inputs: Vec<(usize, char)>, others: Vec<usize>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let others: HashSet<_> = others.iter().collect();
+
+        let lefts: HashSet<_> = map.left_keys().collect();
+        let rights: HashSet<_> = map.right_keys().collect();
+        let paired_lefts: HashSet<_> = map.iter().map(|(a, _)| a).collect();
+        let paired_rights: HashSet<_> = map.iter().map(|(_, b)| b).collect();
+
+        lefts == paired_lefts
+            && rights == paired_rights
+            && lefts
+                .difference(&others)
+                .all(|a| map.contains_left(*a) && !others.contains(a))
+    }
+}
+
+quickcheck! {
+    fn zero_capacity(inputs: Vec<(usize, char)>, a: usize, b: char) -> bool {
+        let mut map = BiMap::with_capacity(0);
+        if map.get_left(&a).is_some()
+            || map.get_right(&b).is_some()
+            || map.contains_left(&a)
+            || map.remove_left(&a).is_some()
+            || map.remove_right(&b).is_some()
+        {
+            return false;
+        }
+
+        let mut expected = BiMap::new();
+        for (a, b) in inputs {
+            map.insert(a, b);
+            expected.insert(a, b);
+        }
+
+        map == expected
+    }
+}
+
+#[test]
+fn zero_capacity_try_insert() {
+    let mut map = BiMapBuilder::new().capacity(0).finish();
+    assert_eq!(Ok(()), map.try_insert(1, 'a'));
+    assert_eq!(Some(&'a'), map.get_left(&1));
+    assert_eq!(Some(&1), map.get_right(&'a'));
+}
+
+quickcheck! {
+    fn u128_bitfield(inputs: Vec<(u16, char)>) -> bool {
+        let mut map = BiMapBuilder::new().bitfield::<u128>().finish();
+        let mut expected = BiMap::new();
+        for &(a, b) in &inputs {
+            map.insert(a, b);
+            expected.insert(a, b);
+        }
+
+        expected.iter().all(|(a, b)| map.get_left(a) == Some(b) && map.get_right(b) == Some(a))
+            && map.len() == expected.len()
+    }
+}
+
+quickcheck! {
+    fn usize_bitfield(inputs: Vec<(u16, char)>) -> bool {
+        let mut map = BiMapBuilder::new().bitfield::<usize>().finish();
+        let mut expected = BiMap::new();
+        for &(a, b) in &inputs {
+            map.insert(a, b);
+            expected.insert(a, b);
+        }
+
+        expected.iter().all(|(a, b)| map.get_left(a) == Some(b) && map.get_right(b) == Some(a))
+            && map.len() == expected.len()
+    }
+}
+
+quickcheck! {
+    fn wide_bitfield(inputs: Vec<(u16, char)>) -> bool {
+        let mut map = BiMapBuilder::new()
+            .bitfield::<isomorphism::bitfield::WideBitField<4>>()
+            .finish();
+        let mut expected = BiMap::new();
+        for &(a, b) in &inputs {
+            map.insert(a, b);
+            expected.insert(a, b);
+        }
+
+        expected.iter().all(|(a, b)| map.get_left(a) == Some(b) && map.get_right(b) == Some(a))
+            && map.len() == expected.len()
+    }
+}
+
+#[cfg(feature = "serde")]
+quickcheck! {
+    fn serde_as_map_round_trip(inputs: Vec<(String, u32)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let json = serde_json::to_string(&isomorphism::AsMap(&map)).unwrap();
+        let round_trip: BiMap<String, u32> = serde_json::from_str(&json).unwrap();
+        map == round_trip
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_as_map_is_json_object() {
+    let map = bimap! { "a".to_owned() => 1 };
+    let json = serde_json::to_value(isomorphism::AsMap(&map)).unwrap();
+    assert_eq!(serde_json::json!({ "a": 1 }), json);
+}
+
+#[cfg(feature = "serde")]
+quickcheck! {
+    fn serde_is_flat_pair_sequence(inputs: Vec<(u16, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let pairs: Vec<(u16, char)> = map.iter().map(|(&a, &b)| (a, b)).collect();
+
+        // bincode isn't self describing, so this only matches if each pair is written as a plain
+        // tuple in a length prefixed sequence
+        let bytes = bincode::serialize(&map).unwrap();
+        let round_trip: BiMap<u16, char> = bincode::deserialize(&bytes).unwrap();
+        bytes == bincode::serialize(&pairs).unwrap() && map == round_trip
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_pair_bytes() {
+    let map = bimap! { 1u16 => 'a' };
+    let bytes = bincode::serialize(&map).unwrap();
+    // a u64 length, then the u16 and the char's UTF-8 encoding, with nothing between them
+    assert_eq!(vec![1, 0, 0, 0, 0, 0, 0, 0, 1, 0, b'a'], bytes);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_pair_format() {
+    let map = bimap! { 1 => 'a' };
+    let json = serde_json::to_value(&map).unwrap();
+    assert_eq!(serde_json::json!([[1, "a"]]), json);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_duplicate_right_key_is_error() {
+    let error = serde_json::from_str::<BiMap<String, u32>>(r#"{"a": 1, "b": 1}"#).unwrap_err();
+    assert!(error.to_string().contains("duplicate key in bimap"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_duplicate_left_key_is_error() {
+    let error = serde_json::from_str::<BiMap<String, u32>>(r#"{"a": 1, "a": 2}"#).unwrap_err();
+    assert!(error.to_string().contains("duplicate key in bimap"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_too_many_pairs_for_pair_index_is_error() {
+    use isomorphism::Lenient;
+    use std::collections::HashMap;
+
+    // more distinct pairs than a u16 pair index can ever place
+    let pairs: HashMap<u32, u32> = (0..1 << 17).map(|i| (i, i)).collect();
+    let json = serde_json::to_string(&pairs).unwrap();
+
+    type Small = BiMap<u32, u32, RandomState, RandomState, u32, u16>;
+    let error = serde_json::from_str::<Small>(&json).err().unwrap();
+    assert!(error
+        .to_string()
+        .contains("too many pairs for the pair index type"));
+
+    // the lenient reader reports the same error instead of panicking
+    let error = serde_json::from_str::<Lenient<Small>>(&json).err().unwrap();
+    assert!(error
+        .to_string()
+        .contains("too many pairs for the pair index type"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_lenient_allows_duplicates() {
+    let isomorphism::Lenient(map) =
+        serde_json::from_str::<isomorphism::Lenient<BiMap<String, u32>>>(r#"{"a": 1, "b": 1}"#)
+            .unwrap();
+    assert_eq!(bimap! { "b".to_owned() => 1 }, map);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn rayon_sum_matches_sequential() {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    let map: BiMap<u64, u64> = (0..100_000).map(|x| (x, x * 3 + 1)).collect();
+    let sequential: u64 = map.iter().map(|(a, b)| a ^ b).sum();
+    let parallel: u64 = (&map).into_par_iter().map(|(a, b)| a ^ b).sum();
+    assert_eq!(sequential, parallel);
+
+    let collected: Vec<_> = (&map).into_par_iter().collect();
+    assert_eq!(map.len(), collected.len());
+}
+
+#[cfg(feature = "rayon")]
+quickcheck! {
+    fn rayon_visits_every_pair(inputs: Vec<(u16, char)>) -> bool {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let mut parallel: Vec<_> = (&map).into_par_iter().collect();
+        let mut sequential: Vec<_> = map.iter().collect();
+        parallel.sort();
+        sequential.sort();
+        parallel == sequential
+    }
+}
+
+quickcheck! {
+    fn get_key_value(inputs: Vec<(u16, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        map.iter().all(|(a, b)| {
+            map.get_left_key_value(a) == Some((a, b)) && map.get_right_key_value(b) == Some((b, a))
+        }) && map.get_left_key_value(&u16::MAX).map(|(_, b)| b) == map.get_left(&u16::MAX)
+    }
+}
+
+quickcheck! {
+    fn remove_pairs(inputs: Vec<(u16, char)>) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let pairs: Vec<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+
+        for (i, &(a, b)) in pairs.iter().enumerate() {
+            let removed = if i % 2 == 0 {
+                map.remove_left_pair(&a)
+            } else {
+                map.remove_right_pair(&b)
+            };
+            if removed != Some((a, b)) || map.contains_left(&a) || map.contains_right(&b) {
+                return false;
+            }
+        }
+
+        map.is_empty()
+    }
+}
+
+quickcheck! {
+    fn remove_pair_matches_get(inputs: Vec<(u16, char)>, a: u16, b: char) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let expected = map.clone();
+        let paired = map.get_left(&a) == Some(&b);
+
+        map.remove_pair(&a, &b) == paired
+            && if paired {
+                map.len() == expected.len() - 1 && !map.contains_left(&a) && !map.contains_right(&b)
+            } else {
+                map == expected
+            }
+    }
+}
+
+#[test]
+fn remove_pair_cases() {
+    let mut map = BiMap::new();
+    map.insert("Hello".to_owned(), "World".to_owned());
+    map.insert("Goodbye".to_owned(), "Moon".to_owned());
+
+    // both keys are present, but not paired with each other
+    assert!(!map.remove_pair("Hello", "Moon"));
+    // the left key is present, but the right key is not
+    assert!(!map.remove_pair("Hello", "Sun"));
+    // neither key is present
+    assert!(!map.remove_pair("Hi", "Sun"));
+    assert_eq!(2, map.len());
+
+    assert!(map.remove_pair("Hello", "World"));
+    assert_eq!(1, map.len());
+    assert_eq!(None, map.get_left("Hello"));
+    assert_eq!(None, map.get_right("World"));
+    assert!(!map.remove_pair("Hello", "World"));
+}
+
+quickcheck! {
+    fn try_from_hash_map(inputs: Vec<(u16, char)>) -> bool {
+        use std::collections::HashMap;
+        use std::convert::TryFrom;
+
+        let hash_map: HashMap<_, _> = inputs.into_iter().collect();
+        let distinct: HashSet<_> = hash_map.values().collect();
+        match BiMap::try_from(hash_map.clone()) {
+            Ok(map) => {
+                distinct.len() == hash_map.len()
+                    && map.len() == hash_map.len()
+                    && hash_map.iter().all(|(a, b)| map.get_left(a) == Some(b))
+            }
+            Err(isomorphism::DuplicateRightError(value)) => {
+                distinct.len() < hash_map.len()
+                    && hash_map.values().filter(|&&b| b == value).count() > 1
+            }
+        }
+    }
+}
+
+#[test]
+fn try_from_hash_map_collision() {
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    let mut hash_map = HashMap::new();
+    hash_map.insert(1, 'a');
+    hash_map.insert(2, 'b');
+    hash_map.insert(3, 'a');
+    let error = BiMap::try_from(hash_map).unwrap_err();
+    assert_eq!(isomorphism::DuplicateRightError('a'), error);
+    assert_eq!(
+        "the value 'a' is mapped to by more than one key",
+        error.to_string()
+    );
+}
+
+quickcheck! {
+    fn into_hashmaps(inputs: Vec<(u16, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let left = map.clone().into_left_hashmap();
+        let right = map.clone().into_right_hashmap();
+
+        left.len() == map.len()
+            && right.len() == map.len()
+            && map
+                .iter()
+                .all(|(a, b)| left.get(a) == Some(b) && right.get(b) == Some(a))
+    }
+}
+
+quickcheck! {
+    fn append(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        let mut map: BiMap<_, _> = first.iter().cloned().collect();
+        let mut other: BiMap<_, _> = second.iter().cloned().collect();
+        let other_capacity = other.capacity();
+
+        let mut expected = map.clone();
+        for (&a, &b) in other.iter() {
+            expected.insert(a, b);
+        }
+
+        map.append(&mut other);
+        map == expected && other.is_empty() && other.capacity() == other_capacity
+    }
+}
+
+quickcheck! {
+    fn merge_with_keep_other_matches_append(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        use isomorphism::Keep;
+
+        let mut map: BiMap<_, _> = first.iter().cloned().collect();
+        let other: BiMap<_, _> = second.iter().cloned().collect();
+        let mut expected = map.clone();
+        expected.append(&mut other.clone());
+
+        map.merge_with(other, |_, _, _, _| Keep::KeepOther);
+        map == expected
+    }
+}
+
+quickcheck! {
+    fn merge_with_keep_self(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        use isomorphism::Keep;
+
+        let mut map: BiMap<_, _> = first.iter().cloned().collect();
+        let original = map.clone();
+        let other: BiMap<_, _> = second.iter().cloned().collect();
+        let fresh: Vec<_> = other
+            .iter()
+            .filter(|(a, b)| !original.contains_left(a) && !original.contains_right(b))
+            .map(|(&a, &b)| (a, b))
+            .collect();
+
+        map.merge_with(other, |_, _, _, _| Keep::KeepSelf);
+        original.iter().all(|(a, b)| map.get_left(a) == Some(b))
+            && fresh.iter().all(|(a, b)| map.get_left(a) == Some(b))
+            && map.len() == original.len() + fresh.len()
+    }
+}
+
+#[test]
+fn merge_with_both_sides_collide() {
+    use isomorphism::Keep;
+
+    let base = bimap! { 1 => 'a', 2 => 'b' };
+    let other = bimap! { 1 => 'b' };
+
+    // the incoming pair is resolved against (1, 'a') and then (2, 'b')
+    let mut calls = Vec::new();
+    let mut map = base.clone();
+    map.merge_with(other.clone(), |&l, &r, _, _| {
+        calls.push((l, r));
+        Keep::KeepOther
+    });
+    assert_eq!(vec![(1, 'a'), (2, 'b')], calls);
+    assert_eq!(bimap! { 1 => 'b' }, map);
+
+    // keeping either existing pair means the incoming pair is dropped, so nothing is evicted
+    // both conflicts are still resolved, even when the first one keeps its pair
+    for keep_first in [true, false] {
+        let mut calls = 0;
+        let mut map = base.clone();
+        map.merge_with(other.clone(), |&l, _, _, _| {
+            calls += 1;
+            if (l == 1) == keep_first {
+                Keep::KeepSelf
+            } else {
+                Keep::KeepOther
+            }
+        });
+        assert_eq!(2, calls);
+        assert_eq!(base, map);
+    }
+
+    // a pair that matches an existing pair exactly is only resolved once
+    let mut calls = 0;
+    let mut map = base.clone();
+    map.merge_with(bimap! { 1 => 'a' }, |_, _, _, _| {
+        calls += 1;
+        Keep::KeepOther
+    });
+    assert_eq!(1, calls);
+    assert_eq!(base, map);
+}
+
+#[test]
+fn hasher_accessors_need_no_bounds() {
+    // a key type that can't be hashed can still have its map's hashers inspected
+    struct Unhashable;
+
+    let map: BiMap<Unhashable, Unhashable> = BiMapBuilder::new().finish();
+    let _ = (map.left_hasher(), map.right_hasher());
+}
+
+quickcheck! {
+    fn try_reserve(inputs: Vec<(u16, char)>, additional: u16) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let expected = map.clone();
+        let additional = additional as usize;
+
+        if map.try_reserve(additional).is_err() || map.capacity() < map.len() + additional {
+            return false;
+        }
+
+        let overflow = map.try_reserve(usize::MAX);
+        overflow == Err(isomorphism::TryReserveError::CapacityOverflow) && map == expected
+    }
+}
+
+quickcheck! {
+    fn extend_from_references(first: Vec<(u8, char)>, second: Vec<(u8, char)>) -> bool {
+        let mut map: BiMap<_, _> = first.into_iter().collect();
+        let other: BiMap<_, _> = second.into_iter().collect();
+
+        let mut expected = map.clone();
+        expected.extend(other.iter().map(|(&a, &b)| (a, b)));
+
+        map.extend(other.iter());
+        map == expected
+    }
+}
+
+quickcheck! {
+    fn collect_from_references(inputs: Vec<(u8, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let copy: BiMap<_, _> = map.iter().collect();
+        let evens: BiMap<_, _> = map.iter().filter(|(&a, _)| a % 2 == 0).collect();
+
+        copy == map
+            && evens.len() == map.left_keys().filter(|&&a| a % 2 == 0).count()
+            && evens.iter().all(|(a, b)| map.get_left(a) == Some(b))
+    }
+}
+
+quickcheck! {
+    fn into_iter_both_ends(inputs: Vec<(u16, char)>, pattern: Vec<bool>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let expected: HashSet<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+
+        let mut iter = map.into_iter();
+        let mut seen = HashSet::new();
+        let mut from_back = pattern.into_iter().cycle();
+        loop {
+            let next = if from_back.next().unwrap_or(false) {
+                iter.next_back()
+            } else {
+                iter.next()
+            };
+            match next {
+                Some(pair) => {
+                    if !seen.insert(pair) {
+                        return false;
+                    }
+                }
+                None => break,
+            }
+            if iter.len() != expected.len() - seen.len() {
+                return false;
+            }
+        }
+
+        seen == expected && iter.next().is_none() && iter.next_back().is_none()
+    }
+}
+
+quickcheck! {
+    fn pop_until_empty(inputs: Vec<(u16, char)>) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let expected: HashSet<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+
+        let mut popped = HashSet::new();
+        while let Some((a, b)) = map.pop() {
+            if !popped.insert((a, b)) || map.contains_left(&a) || map.contains_right(&b) {
+                return false;
+            }
+        }
+
+        map.is_empty() && popped == expected
+    }
+}
+
+quickcheck! {
+    fn pop_interleaved_with_insert(first: Vec<(u16, u16)>, second: Vec<(u16, u16)>) -> bool {
+        let mut map: BiMap<_, _> = first.into_iter().collect();
+        let mut model = map.clone();
+
+        // pairs inserted between pops may land behind where the last pop found its pair
+        let mut second = second.into_iter();
+        while let Some((a, b)) = map.pop() {
+            if model.remove_left(&a) != Some(b) {
+                return false;
+            }
+            if let Some((a, b)) = second.next() {
+                map.insert(a, b);
+                model.insert(a, b);
+            }
+        }
+
+        map.is_empty() && model.is_empty()
+    }
+}
+
+#[test]
+fn pop_large_map() {
+    // popping every pair in turn should only walk the buckets once
+    let mut map: BiMap<u32, u32> = (0..200_000).map(|i| (i, i)).collect();
+    let mut popped = 0;
+    while map.pop().is_some() {
+        popped += 1;
+    }
+    assert_eq!(200_000, popped);
+}
+
+quickcheck! {
+    fn subset_superset_equality(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        let a: BiMap<_, _> = first.into_iter().collect();
+        let b: BiMap<_, _> = second.into_iter().collect();
+
+        (a.is_subset(&b) && b.is_subset(&a)) == (a == b)
+            && a.is_subset(&b) == b.is_superset(&a)
+            && a.is_subset(&a)
+            && a.is_superset(&a)
+    }
+}
+
+quickcheck! {
+    fn equality_ignores_insertion_order(inputs: Vec<(u8, u8)>) -> bool {
+        let a: BiMap<_, _> = inputs.into_iter().collect();
+        let mut pairs: Vec<_> = a.iter().map(|(&l, &r)| (l, r)).collect();
+        pairs.reverse();
+
+        let mut b = BiMap::with_capacity(1000);
+        b.extend(pairs);
+
+        a == b
+    }
+}
+
+#[test]
+fn equality_checks_pairing() {
+    let a: BiMap<_, _> = vec![(1, 'a'), (2, 'b')].into_iter().collect();
+    let b: BiMap<_, _> = vec![(2, 'a'), (1, 'b')].into_iter().collect();
+    let c: BiMap<_, _> = vec![(2, 'b'), (1, 'a')].into_iter().collect();
+    let d: BiMap<_, _> = vec![(1, 'a')].into_iter().collect();
+
+    assert_ne!(a, b);
+    assert_eq!(a, c);
+    assert_ne!(a, d);
+    assert_ne!(d, a);
+}
+
+quickcheck! {
+    fn disjoint(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        let a: BiMap<_, _> = first.into_iter().collect();
+        let b: BiMap<_, _> = second.into_iter().collect();
+        let shared = a.iter().filter(|&(l, r)| b.get_left(l) == Some(r)).count();
+
+        a.is_disjoint(&b) == (shared == 0)
+            && a.is_disjoint(&b) == b.is_disjoint(&a)
+            && a.is_disjoint(&a) == a.is_empty()
+            && (!a.is_disjoint(&b) || a.is_empty() || !a.is_subset(&b))
+    }
+}
+
+quickcheck! {
+    fn intersection_commutes(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        let a: BiMap<_, _> = first.into_iter().collect();
+        let b: BiMap<_, _> = second.into_iter().collect();
+
+        let ab: HashSet<_> = a.intersection(&b).collect();
+        let ba: HashSet<_> = b.intersection(&a).collect();
+        ab == ba
+    }
+}
+
+quickcheck! {
+    fn difference_and_intersection_partition(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        let a: BiMap<_, _> = first.into_iter().collect();
+        let b: BiMap<_, _> = second.into_iter().collect();
+
+        let mut rebuilt: BiMap<u8, u8> = a.difference(&b).collect();
+        let difference_len = rebuilt.len();
+        rebuilt.extend(a.intersection(&b));
+
+        rebuilt == a
+            && difference_len + a.intersection(&b).count() == a.len()
+            && a.difference(&b).all(|(l, r)| b.get_left(l) != Some(r))
+    }
+}
+
+quickcheck! {
+    fn try_get_matches_get(inputs: Vec<(u8, char)>, left: u8, right: char) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        map.try_get_left(&left).ok() == map.get_left(&left)
+            && map.try_get_right(&right).ok() == map.get_right(&right)
+    }
+}
+
+#[test]
+fn try_get_missing() {
+    let map = bimap! { 1 => 'a' };
+    let error = map.try_get_left(&2).unwrap_err();
+    assert_eq!(isomorphism::KeyNotFound, error);
+    assert_eq!("key not found in bimap", error.to_string());
+    assert!(map.try_get_right(&'b').is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_ignores_huge_size_hint() {
+    use serde::de::value::{Error, MapDeserializer};
+    use serde::Deserialize;
+
+    /// Yields its pairs while claiming to have far more of them.
+    struct Lying(std::vec::IntoIter<(u32, u32)>);
+
+    impl Iterator for Lying {
+        type Item = (u32, u32);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (usize::MAX / 2, Some(usize::MAX / 2))
+        }
+    }
+
+    let pairs = Lying(vec![(1, 2), (3, 4), (5, 6)].into_iter());
+    let deserializer = MapDeserializer::<_, Error>::new(pairs);
+    let map = BiMap::<u32, u32>::deserialize(deserializer).unwrap();
+
+    assert_eq!(bimap! { 1 => 2, 3 => 4, 5 => 6 }, map);
+    assert!(map.raw_capacity() <= 8192);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_large_map_without_length() {
+    // serde_json doesn't know how many entries an object has until it has read them all
+    let json = format!(
+        "{{{}}}",
+        (0..10_000)
+            .map(|i| format!("\"{}\": {}", i, i * 2))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    let map: BiMap<String, u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(10_000, map.len());
+    assert!((0..10_000).all(|i| map.get_left(&i.to_string()) == Some(&(i * 2))));
+
+    let isomorphism::Lenient(map): isomorphism::Lenient<BiMap<String, u32>> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(10_000, map.len());
+}
+
+#[test]
+fn insert_all_counts_evictions() {
+    let mut map = bimap! { 0 => 'z' };
+    // 'a' and 'b' are each reused once, and 0 is paired with something new
+    let pairs = vec![(1, 'a'), (2, 'b'), (3, 'a'), (4, 'c'), (5, 'b'), (0, 'd')];
+    assert_eq!(3, map.insert_all(pairs));
+    assert_eq!(bimap! { 0 => 'd', 3 => 'a', 4 => 'c', 5 => 'b' }, map);
+
+    assert_eq!(0, map.insert_all(vec![(6, 'e'), (7, 'f')]));
+    assert_eq!(1, map.insert_all(vec![(6, 'e')]));
+}
+
+quickcheck! {
+    fn insert_all_matches_extend(inputs: Vec<(u8, u8)>) -> bool {
+        let mut counted = BiMap::new();
+        let evicted = counted.insert_all(inputs.clone());
+
+        let mut extended = BiMap::new();
+        extended.extend(inputs.iter().cloned());
+        counted == extended && evicted <= inputs.len()
+    }
+}
+
+#[test]
+fn frozen_concurrent_reads() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let map: BiMap<u32, String> = (0..1000).map(|i| (i, i.to_string())).collect();
+    let frozen = Arc::new(map.freeze());
+
+    let readers: Vec<_> = (0..4)
+        .map(|offset| {
+            let frozen = Arc::clone(&frozen);
+            thread::spawn(move || {
+                (offset..1000).step_by(4).all(|i| {
+                    frozen.get_left(&i) == Some(&i.to_string())
+                        && frozen.get_right(&i.to_string()) == Some(&i)
+                        && frozen.contains_left(&i)
+                })
+            })
+        })
+        .collect();
+    for reader in readers {
+        assert!(reader.join().unwrap());
+    }
+
+    assert_eq!(1000, frozen.len());
+    assert_eq!(1000, frozen.iter().count());
+    assert!(!frozen.contains_right("1000"));
+
+    let mut map = Arc::try_unwrap(frozen).unwrap().thaw();
+    map.insert(1000, "1000".to_owned());
+    assert_eq!(1001, map.len());
+}
+
+quickcheck! {
+    fn iter_sorted_by_left(inputs: Vec<(u16, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let sorted: Vec<_> = map.iter_sorted_by_left().collect();
+
+        sorted.len() == map.len()
+            && sorted.windows(2).all(|window| window[0].0 < window[1].0)
+            && sorted
+                .iter()
+                .all(|&(left, right)| map.get_left(left) == Some(right))
+    }
+}
+
+#[cfg(feature = "serde")]
+quickcheck! {
+    fn serde_sorted_seq_is_deterministic(inputs: Vec<(u16, char)>) -> bool {
+        use isomorphism::AsSortedSeq;
+
+        // each map gets its own randomly seeded hashers, as well as a different insertion order
+        let forwards: BiMap<_, _> = inputs.iter().cloned().collect();
+        let mut backwards = BiMap::new();
+        for &(left, right) in forwards.iter().collect::<Vec<_>>().iter().rev() {
+            backwards.insert(*left, *right);
+        }
+
+        let forwards_json = serde_json::to_vec(&AsSortedSeq(&forwards)).unwrap();
+        let backwards_json = serde_json::to_vec(&AsSortedSeq(&backwards)).unwrap();
+        let AsSortedSeq(round_trip): AsSortedSeq<BiMap<u16, char>> =
+            serde_json::from_slice(&forwards_json).unwrap();
+
+        forwards == backwards && forwards_json == backwards_json && round_trip == forwards
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_sorted_seq_duplicate_is_error() {
+    use isomorphism::AsSortedSeq;
+
+    let error = serde_json::from_str::<AsSortedSeq<BiMap<u32, char>>>(r#"[[1, "a"], [2, "a"]]"#)
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("duplicate key in bimap"));
+
+    // the unsorted output of the regular implementation can be read back in too
+    let map = bimap! { 1 => 'a', 2 => 'b', 3 => 'c' };
+    let json = serde_json::to_string(&map).unwrap();
+    let AsSortedSeq(round_trip): AsSortedSeq<BiMap<u32, char>> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(map, round_trip);
+}
+
+quickcheck! {
+    fn min_max_by_left(inputs: Vec<(u16, char)>) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let sorted: Vec<_> = map.iter_sorted_by_left().collect();
+
+        map.min_by_left() == sorted.first().copied() && map.max_by_left() == sorted.last().copied()
+    }
+}
+
+#[test]
+fn min_max_by_left_cases() {
+    let mut map = BiMap::new();
+    assert_eq!(None, map.min_by_left());
+    assert_eq!(None, map.max_by_left());
+
+    map.extend(vec![(5, 'e'), (2, 'z'), (9, 'a'), (7, 'b')]);
+    assert_eq!(Some((&2, &'z')), map.min_by_left());
+    assert_eq!(Some((&9, &'a')), map.max_by_left());
+
+    map.remove_left(&9);
+    assert_eq!(Some((&7, &'b')), map.max_by_left());
+}
+
+quickcheck! {
+    fn get_many_left_matches_get_left(inputs: Vec<(u8, char)>, keys: (u8, u8, u8, u8)) -> bool {
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let (a, b, c, d) = keys;
+        let keys = [&a, &b, &c, &d];
+
+        map.get_many_left(keys)
+            .iter()
+            .zip(keys.iter())
+            .all(|(&result, key)| result == map.get_left(key))
+    }
+}
+
+#[test]
+fn get_many_left_mixed() {
+    let map = bimap! { 1 => 'a', 2 => 'b', 3 => 'c' };
+    assert_eq!(
+        [Some(&'c'), None, Some(&'a'), Some(&'a'), None],
+        map.get_many_left([&3, &4, &1, &1, &0])
+    );
+    assert_eq!([None; 0], map.get_many_left::<u32, 0>([]));
+}
+
+#[test]
+fn debug_names_both_sides() {
+    let map = bimap! { 1 => 2, 3 => 4 };
+
+    let compact = format!("{:?}", map);
+    assert!(compact.starts_with("BiMap {"));
+    assert!(compact.contains("1: 2"));
+    assert!(compact.contains("3: 4"));
+
+    let pretty = format!("{:#?}", map);
+    assert!(pretty.starts_with("BiMap (left: right) {\n"));
+    assert!(pretty.contains("    1: 2,\n"));
+    assert!(pretty.contains("    3: 4,\n"));
+
+    let frozen = format!("{:?}", BiMap::from([(1, 2)]).freeze());
+    assert_eq!("FrozenBiMap {1: 2}", frozen);
+}
+
+#[test]
+fn memory_footprint_grows_with_bitfield() {
+    let narrow: BiMap<u32, u32, _, _, u8> =
+        BiMapBuilder::new().capacity(1024).bitfield::<u8>().finish();
+    let wide: BiMap<u32, u32, _, _, u64> = BiMapBuilder::new()
+        .capacity(1024)
+        .bitfield::<u64>()
+        .finish();
+
+    let wider: BiMap<u32, u32, _, _, WideBitField<4>> = BiMapBuilder::new()
+        .capacity(1024)
+        .bitfield::<WideBitField<4>>()
+        .finish();
+
+    assert_eq!(narrow.capacity(), wide.capacity());
+    assert_eq!(wide.capacity(), wider.capacity());
+    // the rest of each bucket is 8 byte aligned, so a `u8` neighbourhood is padded out to the
+    // same size as a `u64` one
+    assert!(narrow.memory_footprint() <= wide.memory_footprint());
+    assert!(wide.memory_footprint() < wider.memory_footprint());
+
+    let empty: BiMap<u32, u32> = BiMapBuilder::new().capacity(0).finish();
+    assert_eq!(0, empty.memory_footprint());
+}
+
+#[test]
+fn clone_from_same_capacity() {
+    let mut source = BiMap::with_capacity(1000);
+    let mut target = BiMap::with_capacity(1000);
+    for i in 0..500 {
+        source.insert(i, i.to_string());
+        target.insert(i * 7, (i * 3).to_string());
+    }
+    let capacity = target.capacity();
+    assert_eq!(source.capacity(), capacity);
+
+    target.clone_from(&source);
+    assert_eq!(capacity, target.capacity());
+    assert_eq!(source, target);
+    // the buckets were copied across one for one, so they are visited in the same order
+    assert!(source.iter().eq(target.iter()));
+
+    target.insert(1000, "new".to_owned());
+    assert_eq!(Some(&1000), target.get_right("new"));
+    assert_eq!(None, source.get_right("new"));
+}
+
+#[test]
+fn clone_from_different_capacity() {
+    let mut source = BiMap::with_capacity(1000);
+    source.insert(1, 'a');
+    let mut target = BiMap::new();
+    target.insert(2, 'b');
+
+    target.clone_from(&source);
+    assert_eq!(source.capacity(), target.capacity());
+    assert_eq!(source, target);
+}
+
+#[test]
+fn split_off_allocates_once() {
+    let mut map: BiMap<u32, u32> = (0..10_000).map(|i| (i, i * 7)).collect();
+    let calls = std::cell::Cell::new(0);
+
+    let split = map.split_off(|&left, _| {
+        calls.set(calls.get() + 1);
+        left % 4 == 0
+    });
+    assert_eq!(10_000, calls.get());
+    assert_eq!(2_500, split.len());
+    assert_eq!(7_500, map.len());
+    assert_eq!(BiMap::<u32, u32>::capacity_for(2_500), split.raw_capacity());
+}
+
+#[test]
+fn narrow_pair_index() {
+    let mut narrow: BiMap<u32, u32, _, _, _, u32> = BiMapBuilder::new()
+        .capacity(1024)
+        .pair_index::<u32>()
+        .finish();
+    let mut wide: BiMap<u32, u32> = BiMapBuilder::new().capacity(1024).finish();
+    for i in 0..2000 {
+        narrow.insert(i, i * 3 % 2000);
+        wide.insert(i, i * 3 % 2000);
+    }
+
+    assert_eq!(wide.len(), narrow.len());
+    for (left, right) in wide.iter() {
+        assert_eq!(Some(right), narrow.get_left(left));
+        assert_eq!(Some(left), narrow.get_right(right));
+    }
+    assert_eq!(wide.capacity(), narrow.capacity());
+    assert!(narrow.memory_footprint() < wide.memory_footprint());
+}
+
+#[test]
+fn pair_index_capacity_overflow() {
+    let mut map: BiMap<u32, u32, _, _, _, u16> = BiMapBuilder::new().pair_index::<u16>().finish();
+    map.insert(1, 2);
+
+    assert_eq!(Ok(()), map.try_reserve(1000));
+    assert_eq!(
+        Err(TryReserveError::CapacityOverflow),
+        map.try_reserve(1 << 16)
+    );
+    assert_eq!(Some(&2), map.get_left(&1));
+}
+
+#[test]
+fn pair_index_stops_growing_at_limit() {
+    let mut map: BiMap<u32, u32, _, _, _, u16> = BiMapBuilder::new().pair_index::<u16>().finish();
+
+    // keep inserting well past the point where the map would normally grow beyond 65536 buckets,
+    // until a pair is handed back rather than panicking
+    let mut rejected = None;
+    for i in 0..(1 << 17) {
+        if let Err(pair) = map.insert_bounded(i, i) {
+            rejected = Some(pair);
+            break;
+        }
+    }
+
+    let (left, right) = rejected.expect("a full u16 map rejects some pair");
+    assert_eq!(1 << 16, map.raw_capacity());
+    assert!(map.len() > 1 << 15);
+    assert_eq!(None, map.get_left(&left));
+    assert_eq!(None, map.get_right(&right));
+    assert!((0..left).all(|i| map.get_left(&i) == Some(&i)));
+
+    // replacing a key still works without growing the map
+    assert_eq!(Ok((Some(0), None)), map.insert_bounded(0, 1 << 20));
+    assert_eq!(1 << 16, map.raw_capacity());
+}
+
+type NearLimit = BiMap<u32, u32, RandomState, RandomState, u32, u16>;
+
+// 40,000 pairs, in a map asking for more buckets than a u16 pair index can count
+fn near_limit() -> NearLimit {
+    let mut map: NearLimit = BiMapBuilder::new()
+        .pair_index::<u16>()
+        .capacity(60_000)
+        .finish();
+    assert_eq!(1 << 16, map.raw_capacity());
+    for i in 0..40_000 {
+        map.insert(i, i);
+    }
+    map
+}
+
+#[test]
+fn pair_index_reserve_stops_at_limit() {
+    let mut map = near_limit();
+    map.reserve(1 << 20);
+    assert_eq!(1 << 16, map.raw_capacity());
+
+    map.shrink_to(1 << 20);
+    assert_eq!(1 << 16, map.raw_capacity());
+    assert_eq!(40_000, map.len());
+}
+
+#[test]
+fn pair_index_append_near_limit() {
+    let mut map = near_limit();
+    let mut other = near_limit();
+    map.append(&mut other);
+    assert_eq!(40_000, map.len());
+    assert!(other.is_empty());
+    assert_eq!(1 << 16, map.raw_capacity());
+}
+
+#[test]
+fn pair_index_merge_with_near_limit() {
+    let mut map = near_limit();
+    map.merge_with(near_limit(), |_, _, _, _| Keep::KeepOther);
+    assert_eq!(40_000, map.len());
+    assert!((0..40_000).all(|i| map.get_left(&i) == Some(&i)));
+}
+
+#[cfg(feature = "rayon")]
+quickcheck! {
+    fn rayon_par_build_matches_sequential(inputs: HashSet<u16>) -> bool {
+        use rayon::iter::IntoParallelIterator;
+
+        // left and right keys are both unique, so there are no collisions to resolve
+        let pairs: Vec<_> = inputs.into_iter().map(|x| (x, u32::from(x) * 3)).collect();
+        let sequential: BiMap<_, _> = pairs.iter().cloned().collect();
+        let parallel: BiMap<_, _> = BiMap::par_build(pairs.into_par_iter());
+        parallel == sequential
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn rayon_par_extend_near_limit() {
+    use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+    let mut map = near_limit();
+    map.par_extend((0..40_000u32).into_par_iter().map(|i| (i, i)));
+    assert_eq!(40_000, map.len());
+    assert_eq!(1 << 16, map.raw_capacity());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn rayon_par_extend_keeps_existing_pairs() {
+    use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+    let mut map = bimap! { 0 => 0, 1 => 1 };
+    // (1, 2) evicts (1, 1), but nothing touches (0, 0)
+    map.par_extend((1..10_000u32).into_par_iter().map(|x| (x, x + 1)));
+    assert_eq!(10_000, map.len());
+    assert_eq!(Some(&0), map.get_left(&0));
+    assert_eq!(Some(&2), map.get_left(&1));
+    assert_eq!(None, map.get_right(&1));
+}